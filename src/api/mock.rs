@@ -0,0 +1,140 @@
+//! In memory implementation of [`TwitchApi`] for tests
+//!
+//! Calls are expected in the order they were programmed, each returning its canned
+//! result. A call that was not expected panics with a description of the call.
+//!
+//! ```
+//! # use twitch_api_rs::api::{TwitchApi, mock::MockApi};
+//! # use twitch_api_rs::resource::users::get_users::*;
+//! # #[tokio::main]
+//! # async fn main() {
+//! let job = GetUsersJob {
+//!     ids: vec![],
+//!     logins: vec!["TheHoodlum12".into()],
+//! };
+//!
+//! let api = MockApi::new();
+//! api.expect_get_users(
+//!     job.clone(),
+//!     Ok(GetUsersResponse {
+//!         users: vec![],
+//!         extra: Default::default(),
+//!     }),
+//! );
+//!
+//! let resp = api.get_users(job).await.unwrap();
+//! assert!(resp.users.is_empty());
+//!
+//! api.assert_done();
+//! # }
+//! ```
+
+use async_trait::async_trait;
+use std::collections::VecDeque;
+use std::sync::Mutex;
+
+use super::{ApiResult, TwitchApi};
+use crate::resource::channels::get_channel_information::*;
+use crate::resource::clips::get_clips::*;
+use crate::resource::users::get_users::*;
+
+/// A single programmed call to a [`MockApi`]
+#[derive(Debug)]
+enum Expectation {
+    Users(GetUsersJob, ApiResult<GetUsersResponse>),
+    Clips(GetClipsJob, ApiResult<GetClipsResponse>),
+    ChannelInformation(
+        GetChannelInformationJob,
+        ApiResult<GetChannelInformationResponse>,
+    ),
+}
+
+/// A [`TwitchApi`] that returns programmed results instead of making requests
+///
+/// See module level documentation for usage.
+#[derive(Debug, Default)]
+pub struct MockApi {
+    expected: Mutex<VecDeque<Expectation>>,
+}
+
+impl MockApi {
+    /// Create a mock with no expected calls
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn expect(&self, expectation: Expectation) -> &Self {
+        self.expected.lock().unwrap().push_back(expectation);
+        self
+    }
+
+    fn next(&self, call: &str) -> Expectation {
+        match self.expected.lock().unwrap().pop_front() {
+            Some(expectation) => expectation,
+            None => panic!("MockApi got unexpected call {}", call),
+        }
+    }
+
+    /// Expect a call to [`TwitchApi::get_users`] with `job`, returning `result`
+    pub fn expect_get_users(&self, job: GetUsersJob, result: ApiResult<GetUsersResponse>) -> &Self {
+        self.expect(Expectation::Users(job, result))
+    }
+
+    /// Expect a call to [`TwitchApi::get_clips`] with `job`, returning `result`
+    pub fn expect_get_clips(&self, job: GetClipsJob, result: ApiResult<GetClipsResponse>) -> &Self {
+        self.expect(Expectation::Clips(job, result))
+    }
+
+    /// Expect a call to [`TwitchApi::get_channel_information`] with `job`, returning
+    /// `result`
+    pub fn expect_get_channel_information(
+        &self,
+        job: GetChannelInformationJob,
+        result: ApiResult<GetChannelInformationResponse>,
+    ) -> &Self {
+        self.expect(Expectation::ChannelInformation(job, result))
+    }
+
+    /// The amount of programmed calls that have not been made yet
+    pub fn remaining(&self) -> usize {
+        self.expected.lock().unwrap().len()
+    }
+
+    /// Panic if any programmed calls have not been made
+    pub fn assert_done(&self) {
+        let expected = self.expected.lock().unwrap();
+        if !expected.is_empty() {
+            panic!("MockApi still expected calls {:#?}", *expected);
+        }
+    }
+}
+
+#[async_trait]
+impl TwitchApi for MockApi {
+    async fn get_users(&self, job: GetUsersJob) -> ApiResult<GetUsersResponse> {
+        match self.next(&format!("get_users({:?})", job)) {
+            Expectation::Users(expected, result) if expected == job => result,
+            other => panic!("MockApi expected {:?} but got get_users({:?})", other, job),
+        }
+    }
+
+    async fn get_clips(&self, job: GetClipsJob) -> ApiResult<GetClipsResponse> {
+        match self.next(&format!("get_clips({:?})", job)) {
+            Expectation::Clips(expected, result) if expected == job => result,
+            other => panic!("MockApi expected {:?} but got get_clips({:?})", other, job),
+        }
+    }
+
+    async fn get_channel_information(
+        &self,
+        job: GetChannelInformationJob,
+    ) -> ApiResult<GetChannelInformationResponse> {
+        match self.next(&format!("get_channel_information({:?})", job)) {
+            Expectation::ChannelInformation(expected, result) if expected == job => result,
+            other => panic!(
+                "MockApi expected {:?} but got get_channel_information({:?})",
+                other, job
+            ),
+        }
+    }
+}
@@ -0,0 +1,122 @@
+//! High level, object safe interface over the endpoints this crate supports
+//!
+//! Business logic can be written against [`TwitchApi`] and given a [`HelixApi`] in
+//! production or a [`mock::MockApi`] in tests, so that it can be tested without making
+//! any http requests.
+//!
+//! ```
+//! # use twitch_api_rs::api::*;
+//! # use twitch_api_rs::values::users::UserId;
+//! # use twitch_api_rs::resource::users::get_users::GetUsersJob;
+//! /// Look up the ids of users by their login names
+//! async fn user_ids(api: &dyn TwitchApi, logins: Vec<String>) -> ApiResult<Vec<UserId>> {
+//!     let job = GetUsersJob {
+//!         ids: vec![],
+//!         logins: logins.into_iter().map(Into::into).collect(),
+//!     };
+//!
+//!     Ok(api.get_users(job).await?.users.into_iter().map(|u| u.id).collect())
+//! }
+//! ```
+
+pub mod mock;
+
+use async_trait::async_trait;
+use reqwest::Client;
+use std::sync::Arc;
+
+use crate::auth::AppOrUserToken;
+use crate::requests::{CommonResponseCodes, Request, RequestError, SerializableRequest};
+use crate::resource::channels::get_channel_information::*;
+use crate::resource::clips::get_clips::*;
+use crate::resource::users::get_users::*;
+
+/// Result returned by all methods of [`TwitchApi`]
+pub type ApiResult<T> = Result<T, RequestError<CommonResponseCodes>>;
+
+/// The endpoints of the twitch api, as an object safe trait
+///
+/// Each method takes the job for its request, see
+/// [`crate::requests::SerializableRequest`], and returns the response of that request.
+#[async_trait]
+pub trait TwitchApi: Send + Sync {
+    /// Make a request to the [`Get Users`] endpoint
+    ///
+    /// [`Get Users`]: https://dev.twitch.tv/docs/api/reference#get-users
+    async fn get_users(&self, job: GetUsersJob) -> ApiResult<GetUsersResponse>;
+
+    /// Make a request to the [`Get Clips`] endpoint
+    ///
+    /// [`Get Clips`]: https://dev.twitch.tv/docs/api/reference#get-clips
+    async fn get_clips(&self, job: GetClipsJob) -> ApiResult<GetClipsResponse>;
+
+    /// Make a request to the [`Get Channel Information`] endpoint
+    ///
+    /// [`Get Channel Information`]: https://dev.twitch.tv/docs/api/reference#get-channel-information
+    async fn get_channel_information(
+        &self,
+        job: GetChannelInformationJob,
+    ) -> ApiResult<GetChannelInformationResponse>;
+}
+
+/// Implementation of [`TwitchApi`] that makes requests to twitch with [`reqwest`]
+#[derive(Debug, Clone)]
+pub struct HelixApi<A>
+where
+    A: AppOrUserToken,
+{
+    client: Arc<Client>,
+    auth: A,
+}
+
+impl<A> HelixApi<A>
+where
+    A: AppOrUserToken,
+{
+    /// Create an api that sends every request with `auth`
+    pub fn new<C>(client: C, auth: A) -> Self
+    where
+        C: Into<Arc<Client>>,
+    {
+        Self {
+            client: client.into(),
+            auth,
+        }
+    }
+
+    /// Replace the authorization token used for requests
+    pub fn set_auth(&mut self, auth: A) -> &mut Self {
+        self.auth = auth;
+        self
+    }
+}
+
+#[async_trait]
+impl<A> TwitchApi for HelixApi<A>
+where
+    A: AppOrUserToken + Send + Sync,
+{
+    async fn get_users(&self, job: GetUsersJob) -> ApiResult<GetUsersResponse> {
+        GetUsersRequest::<A>::from_job(job)
+            .set_auth(self.auth.clone())
+            .make_request(&*self.client)
+            .await
+    }
+
+    async fn get_clips(&self, job: GetClipsJob) -> ApiResult<GetClipsResponse> {
+        GetClipsRequest::<A>::from_job(job)
+            .set_auth(self.auth.clone())
+            .make_request(&*self.client)
+            .await
+    }
+
+    async fn get_channel_information(
+        &self,
+        job: GetChannelInformationJob,
+    ) -> ApiResult<GetChannelInformationResponse> {
+        GetChannelInformationRequest::<A>::from_job(job)
+            .set_auth(self.auth.clone())
+            .make_request(&*self.client)
+            .await
+    }
+}
@@ -0,0 +1,254 @@
+//! [`Authorization Code`] Flow
+//!
+//! Send the user to an [`AuthorizeUrl`], and when twitch redirects them back read the code
+//! with [`parse_callback`]. Exchange the code for a [`UserAuthToken`] with a
+//! [`CodeExchangeRequest`], or let [`crate::client::Client::user_auth`] do it.
+//!
+//! ```no_run
+//! # use twitch_api_rs::auth::authorization_code::*;
+//! # use twitch_api_rs::auth::scopes::{Scope, ScopeSet};
+//! # use twitch_api_rs::requests::Request;
+//! # #[tokio::main]
+//! # async fn main() {
+//! # let (client_id, client_secret) = (String::new(), String::new());
+//! # let redirect = String::new();
+//! let mut scopes = ScopeSet::new();
+//! scopes.insert(Scope::UserReadEmail);
+//!
+//! let authorize = AuthorizeUrl::new(
+//!     ResponseType::Code,
+//!     client_id.clone(),
+//!     "http://localhost:3000/callback",
+//! )
+//!     .scopes(scopes)
+//!     .force_verify(true);
+//!
+//! eprintln!("Open {} to log in", authorize.build());
+//! // ... twitch redirects the user to `redirect`
+//! let code = parse_callback(&redirect, authorize.csrf_state()).expect("Invalid redirect");
+//!
+//! let token = CodeExchangeRequest::from((
+//!     client_id.clone(),
+//!     client_secret,
+//!     code.code,
+//!     "http://localhost:3000/callback",
+//! ))
+//! .make_request(&reqwest::Client::new())
+//! .await
+//! .expect("Could not exchange code")
+//! .into_token(client_id);
+//! # }
+//! ```
+//!
+//! [`Authorization Code`]: https://dev.twitch.tv/docs/authentication/getting-tokens-oauth/#oauth-authorization-code-flow
+
+use serde::{ser::SerializeMap, Deserialize, Serialize, Serializer};
+
+use super::callback::callback_params;
+pub use super::callback::{AuthorizeUrl, CallbackError, CsrfState, ResponseType};
+use super::scopes::ScopeSet;
+use super::user_token::UserAuthToken;
+use super::{ClientId, ClientSecret};
+use crate::requests::*;
+
+/// The code from a redirect after the user authorized the application
+#[derive(Debug, Clone)]
+pub struct AuthorizationCode {
+    /// Code to exchange for a token with [`CodeExchangeRequest`]
+    pub code: String,
+
+    /// The scopes the user granted
+    pub scopes: ScopeSet,
+}
+
+/// Read the code from the url twitch redirected the user to, checking that it was sent
+/// with `state`
+pub fn parse_callback(
+    redirect: &str,
+    state: &CsrfState,
+) -> Result<AuthorizationCode, CallbackError> {
+    let params = callback_params(redirect, state, false)?;
+    let get = |name: &str| params.iter().find(|(k, _)| k == name).map(|(_, v)| v);
+
+    Ok(AuthorizationCode {
+        code: get("code").ok_or(CallbackError::Missing("code"))?.clone(),
+        scopes: get("scope")
+            .map(|s| s.split_whitespace().collect())
+            .unwrap_or_default(),
+    })
+}
+
+#[derive(Debug)]
+/// Do not use directly, instead use [`CodeExchangeRequest`]
+pub struct CodeExchangeParams {
+    client_id: Option<ClientId>,
+    client_secret: Option<ClientSecret>,
+    code: Option<String>,
+    redirect_uri: Option<String>,
+}
+
+impl Serialize for CodeExchangeParams {
+    fn serialize<S>(&self, ser: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let mut map = ser.serialize_map(Some(5))?;
+        map.serialize_entry("client_id", &self.client_id)?;
+        map.serialize_entry("client_secret", &self.client_secret)?;
+        map.serialize_entry("code", &self.code)?;
+        map.serialize_entry("grant_type", "authorization_code")?;
+        map.serialize_entry("redirect_uri", &self.redirect_uri)?;
+        map.end()
+    }
+}
+
+impl ParametersExt for CodeExchangeParams {}
+
+#[derive(Debug)]
+/// Request to exchange an [`AuthorizationCode`] for a user token
+pub struct CodeExchangeRequest {
+    params: CodeExchangeParams,
+}
+
+#[cfg_attr(feature = "nightly", doc(spotlight))]
+impl Request for CodeExchangeRequest {
+    const ENDPOINT: &'static str = "https://id.twitch.tv/oauth2/token";
+
+    type Headers = ();
+    type Parameters = CodeExchangeParams;
+    type Body = ();
+
+    type Response = CodeExchangeResponse;
+
+    type ErrorCodes = CommonResponseCodes;
+
+    const METHOD: reqwest::Method = reqwest::Method::POST;
+
+    fn builder() -> Self {
+        Self {
+            params: CodeExchangeParams {
+                client_id: None,
+                client_secret: None,
+                code: None,
+                redirect_uri: None,
+            },
+        }
+    }
+
+    fn headers(&self) -> &Self::Headers {
+        &()
+    }
+    fn parameters(&self) -> &Self::Parameters {
+        &self.params
+    }
+    fn body(&self) -> &Self::Body {
+        &()
+    }
+
+    fn ready(&self) -> Result<(), RequestError<Self::ErrorCodes>> {
+        let missing = if self.params.client_id.is_none() {
+            "client_id"
+        } else if self.params.client_secret.is_none() {
+            "client_secret"
+        } else if self.params.code.is_none() {
+            "code"
+        } else if self.params.redirect_uri.is_none() {
+            "redirect_uri"
+        } else {
+            return Ok(());
+        };
+
+        Err(RequestError::MalformedRequest(format!(
+            "field {} must be set",
+            missing
+        )))
+    }
+}
+
+impl CodeExchangeRequest {
+    /// Set the client_id
+    pub fn set_client_id<I: Into<ClientId>>(&mut self, client_id: I) -> &mut Self {
+        self.params.client_id.replace(client_id.into());
+        self
+    }
+
+    /// Set the client_secret
+    pub fn set_client_secret<S: Into<ClientSecret>>(&mut self, client_secret: S) -> &mut Self {
+        self.params.client_secret.replace(client_secret.into());
+        self
+    }
+
+    /// Set the code returned in the redirect
+    pub fn set_code<S: Into<String>>(&mut self, code: S) -> &mut Self {
+        self.params.code.replace(code.into());
+        self
+    }
+
+    /// Set the redirect uri, must be the same one used in the [`AuthorizeUrl`]
+    pub fn set_redirect_uri<S: Into<String>>(&mut self, redirect_uri: S) -> &mut Self {
+        self.params.redirect_uri.replace(redirect_uri.into());
+        self
+    }
+}
+
+/// Build a complete request from `(client_id, client_secret, code, redirect_uri)`
+impl<I, S, C, R> From<(I, S, C, R)> for CodeExchangeRequest
+where
+    I: Into<ClientId>,
+    S: Into<ClientSecret>,
+    C: Into<String>,
+    R: Into<String>,
+{
+    fn from((client_id, client_secret, code, redirect_uri): (I, S, C, R)) -> Self {
+        Self {
+            params: CodeExchangeParams {
+                client_id: Some(client_id.into()),
+                client_secret: Some(client_secret.into()),
+                code: Some(code.into()),
+                redirect_uri: Some(redirect_uri.into()),
+            },
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+/// Response from a successful [`CodeExchangeRequest`]
+pub struct CodeExchangeResponse {
+    /// The access token sent with requests
+    pub access_token: String,
+
+    /// Token used to get a new access token once this one expires
+    pub refresh_token: String,
+
+    /// The amount of seconds until the token expires
+    pub expires_in: u32,
+
+    /// The scopes the user granted, as twitch names them
+    #[serde(default)]
+    pub scope: Vec<String>,
+
+    /// Always `bearer`
+    pub token_type: String,
+
+    /// Signed claims about the user, only sent when the `openid` scope was requested
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub id_token: Option<String>,
+}
+
+impl CodeExchangeResponse {
+    /// The scopes the user granted
+    pub fn scopes(&self) -> ScopeSet {
+        self.scope.iter().map(String::as_str).collect()
+    }
+
+    /// Turn the response into a token for the client it was issued to
+    pub fn into_token<I: Into<ClientId>>(self, client_id: I) -> UserAuthToken {
+        let scopes = self.scopes();
+        UserAuthToken::new(
+            self.access_token,
+            Some(self.refresh_token),
+            client_id,
+            scopes,
+        )
+    }
+}
@@ -0,0 +1,219 @@
+//! Values shared by the flows that send the user to twitch and back to a redirect uri,
+//! see [`super::authorization_code`] and [`super::implicit_code`]
+
+use thiserror::Error;
+use url::Url;
+
+use super::scopes::ScopeSet;
+use super::ClientId;
+
+/// Random value sent with an authorize url and checked when twitch redirects back, so
+/// that a redirect can not be forged by another site
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CsrfState(String);
+
+impl CsrfState {
+    /// Create a new state from 16 random bytes
+    pub fn random() -> Self {
+        let mut bytes = [0u8; 16];
+        getrandom::getrandom(&mut bytes).expect("Could not get random bytes for state");
+        Self(bytes.iter().map(|b| format!("{:02x}", b)).collect())
+    }
+
+    /// Use a state that was created earlier, for example one kept in a session
+    pub fn new<S: Into<String>>(state: S) -> Self {
+        Self(state.into())
+    }
+
+    /// The state as it is sent to twitch
+    pub fn secret(&self) -> &str {
+        &self.0
+    }
+
+    /// Check that `returned` is this state, without leaking how much of it matched
+    pub fn verify(&self, returned: &str) -> Result<(), CallbackError> {
+        let (a, b) = (self.0.as_bytes(), returned.as_bytes());
+        let diff = a
+            .iter()
+            .zip(b)
+            .fold(a.len() ^ b.len(), |acc, (x, y)| acc | (x ^ y) as usize);
+
+        if diff == 0 {
+            Ok(())
+        } else {
+            Err(CallbackError::StateMismatch)
+        }
+    }
+}
+
+/// Returned when the redirect back from twitch could not be used
+#[derive(Debug, Error)]
+pub enum CallbackError {
+    #[error("Redirect url could not be parsed: {0}")]
+    /// The redirect url was not a valid url
+    InvalidUrl(#[from] url::ParseError),
+
+    #[error("State returned by twitch did not match the state that was sent")]
+    /// The state was missing or different, the redirect may have been forged
+    StateMismatch,
+
+    #[error("User did not authorize the application: {error} {description}")]
+    /// The user denied access, or twitch rejected the authorization request
+    Denied {
+        /// The error code, for example `access_denied`
+        error: String,
+        /// Why authorization failed
+        description: String,
+    },
+
+    #[error("Redirect did not contain {0}")]
+    /// A value that should have been in the redirect was not there
+    Missing(&'static str),
+}
+
+/// Parse `redirect` and get its query or fragment parameters, checking that the state
+/// matches `state` and that twitch did not report an error
+pub(crate) fn callback_params(
+    redirect: &str,
+    state: &CsrfState,
+    in_fragment: bool,
+) -> Result<Vec<(String, String)>, CallbackError> {
+    let url = Url::parse(redirect)?;
+
+    let params: Vec<(String, String)> = if in_fragment {
+        let fragment = url.fragment().unwrap_or_default();
+        url::form_urlencoded::parse(fragment.as_bytes())
+            .into_owned()
+            .collect()
+    } else {
+        url.query_pairs().into_owned().collect()
+    };
+
+    let get = |name: &str| {
+        params
+            .iter()
+            .find(|(k, _)| k == name)
+            .map(|(_, v)| v.clone())
+    };
+
+    // Twitch reports errors in the query even for the implicit flow
+    let query_error = url
+        .query_pairs()
+        .find(|(k, _)| k == "error")
+        .map(|(_, v)| v.into_owned());
+
+    if let Some(error) = get("error").or(query_error) {
+        let description = get("error_description")
+            .or_else(|| {
+                url.query_pairs()
+                    .find(|(k, _)| k == "error_description")
+                    .map(|(_, v)| v.into_owned())
+            })
+            .unwrap_or_default();
+        return Err(CallbackError::Denied { error, description });
+    }
+
+    state.verify(&get("state").ok_or(CallbackError::StateMismatch)?)?;
+
+    Ok(params)
+}
+
+/// Where users are sent to authorize an application
+pub const AUTHORIZE_URL: &str = "https://id.twitch.tv/oauth2/authorize";
+
+/// Which flow an [`AuthorizeUrl`] starts, sent as its `response_type`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ResponseType {
+    /// The redirect has a code to exchange for a token, see [`super::authorization_code`]
+    Code,
+    /// The redirect has a token in its fragment, see [`super::implicit_code`]
+    Token,
+}
+
+/// Builder for the url that sends the user to twitch to authorize the application
+#[derive(Debug, Clone)]
+pub struct AuthorizeUrl {
+    response_type: ResponseType,
+    client_id: ClientId,
+    redirect_uri: String,
+    pub(crate) scopes: ScopeSet,
+    force_verify: bool,
+    state: CsrfState,
+    pub(crate) id_token: bool,
+    pub(crate) extra: Vec<(&'static str, String)>,
+}
+
+impl AuthorizeUrl {
+    /// Start the flow picked by `response_type` for `client_id`, redirecting back to
+    /// `redirect_uri` which must match one registered for the application. A random state
+    /// is created.
+    pub fn new<I, R>(response_type: ResponseType, client_id: I, redirect_uri: R) -> Self
+    where
+        I: Into<ClientId>,
+        R: Into<String>,
+    {
+        Self {
+            response_type,
+            client_id: client_id.into(),
+            redirect_uri: redirect_uri.into(),
+            scopes: ScopeSet::new(),
+            force_verify: false,
+            state: CsrfState::random(),
+            id_token: false,
+            extra: Vec::new(),
+        }
+    }
+
+    /// Set the scopes the user is asked to grant
+    pub fn scopes(mut self, scopes: ScopeSet) -> Self {
+        self.scopes = scopes;
+        self
+    }
+
+    /// Ask the user to authorize again even if they already did before
+    pub fn force_verify(mut self, force_verify: bool) -> Self {
+        self.force_verify = force_verify;
+        self
+    }
+
+    /// Use `state` instead of a random one
+    pub fn state(mut self, state: CsrfState) -> Self {
+        self.state = state;
+        self
+    }
+
+    /// The state that must be returned with the redirect
+    pub fn csrf_state(&self) -> &CsrfState {
+        &self.state
+    }
+
+    /// The client the user is asked to authorize
+    pub fn client_id(&self) -> &ClientId {
+        &self.client_id
+    }
+
+    /// Get the url to send the user to
+    pub fn build(&self) -> String {
+        // The id token of the code flow comes with the code exchange instead
+        let response_type = match (self.response_type, self.id_token) {
+            (ResponseType::Code, _) => "code",
+            (ResponseType::Token, false) => "token",
+            (ResponseType::Token, true) => "token id_token",
+        };
+
+        let mut url = Url::parse(AUTHORIZE_URL).expect("Authorize url is valid");
+        url.query_pairs_mut()
+            .append_pair("response_type", response_type)
+            .append_pair("client_id", &self.client_id)
+            .append_pair("redirect_uri", &self.redirect_uri)
+            .append_pair("scope", &self.scopes.to_string())
+            .append_pair(
+                "force_verify",
+                if self.force_verify { "true" } else { "false" },
+            )
+            .append_pair("state", self.state.secret())
+            .extend_pairs(&self.extra);
+
+        url.into()
+    }
+}
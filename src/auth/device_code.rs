@@ -0,0 +1,406 @@
+//! [`Device Code`] Flow
+//!
+//! For tools that can not open a browser or receive a redirect, such as bots and command
+//! line tools running on a server. Ask twitch for a code with a [`DeviceCodeRequest`] and
+//! show the user where to enter it, then poll with [`poll_token`] until they have
+//! authorized the application.
+//!
+//! ```no_run
+//! # use twitch_api_rs::auth::device_code::*;
+//! # use twitch_api_rs::auth::scopes::{Scope, ScopeSet};
+//! # use twitch_api_rs::requests::Request;
+//! # #[tokio::main]
+//! # async fn main() {
+//! # let client_id = String::new();
+//! let client = reqwest::Client::new();
+//!
+//! let mut scopes = ScopeSet::new();
+//! scopes.insert(Scope::ChatRead);
+//!
+//! let device = DeviceCodeRequest::from((client_id.clone(), scopes.clone()))
+//!     .make_request(&client)
+//!     .await
+//!     .expect("Could not get device code");
+//!
+//! eprintln!(
+//!     "Open {} and enter the code {}",
+//!     device.verification_uri, device.user_code
+//! );
+//!
+//! let token = poll_token(&client, client_id, &device, scopes)
+//!     .await
+//!     .expect("User did not authorize the application");
+//! # }
+//! ```
+//!
+//! [`Device Code`]: https://dev.twitch.tv/docs/authentication/getting-tokens-oauth/#device-code-grant-flow
+
+use serde::{ser::SerializeMap, Deserialize, Serialize, Serializer};
+use std::future::Future;
+use std::time::{Duration, Instant};
+use thiserror::Error;
+
+use super::authorization_code::CodeExchangeResponse;
+use super::scopes::ScopeSet;
+use super::user_token::UserAuthToken;
+use super::ClientId;
+use crate::requests::*;
+
+/// How much longer to wait between polls each time twitch asks to slow down
+const SLOW_DOWN_STEP: Duration = Duration::from_secs(5);
+
+#[derive(Debug)]
+/// Do not use directly, instead use [`DeviceCodeRequest`]
+pub struct DeviceCodeParams {
+    client_id: Option<ClientId>,
+    scopes: ScopeSet,
+}
+
+impl Serialize for DeviceCodeParams {
+    fn serialize<S>(&self, ser: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let mut map = ser.serialize_map(Some(2))?;
+        map.serialize_entry("client_id", &self.client_id)?;
+        map.serialize_entry("scopes", &self.scopes.to_string())?;
+        map.end()
+    }
+}
+
+impl ParametersExt for DeviceCodeParams {}
+
+#[derive(Debug)]
+/// Request for a device code and the user code that the user enters to authorize it
+pub struct DeviceCodeRequest {
+    params: DeviceCodeParams,
+}
+
+#[cfg_attr(feature = "nightly", doc(spotlight))]
+impl Request for DeviceCodeRequest {
+    const ENDPOINT: &'static str = "https://id.twitch.tv/oauth2/device";
+
+    type Headers = ();
+    type Parameters = DeviceCodeParams;
+    type Body = ();
+
+    type Response = DeviceCodeResponse;
+
+    type ErrorCodes = CommonResponseCodes;
+
+    const METHOD: reqwest::Method = reqwest::Method::POST;
+
+    fn builder() -> Self {
+        Self {
+            params: DeviceCodeParams {
+                client_id: None,
+                scopes: ScopeSet::new(),
+            },
+        }
+    }
+
+    fn headers(&self) -> &Self::Headers {
+        &()
+    }
+    fn parameters(&self) -> &Self::Parameters {
+        &self.params
+    }
+    fn body(&self) -> &Self::Body {
+        &()
+    }
+
+    fn ready(&self) -> Result<(), RequestError<Self::ErrorCodes>> {
+        if self.params.client_id.is_none() {
+            Err(RequestError::MalformedRequest(String::from(
+                "field client_id must be set",
+            )))
+        } else {
+            Ok(())
+        }
+    }
+}
+
+impl DeviceCodeRequest {
+    /// Set the client_id
+    pub fn set_client_id<I: Into<ClientId>>(&mut self, client_id: I) -> &mut Self {
+        self.params.client_id.replace(client_id.into());
+        self
+    }
+
+    /// Set the scopes the user is asked to grant
+    pub fn set_scopes(&mut self, scopes: ScopeSet) -> &mut Self {
+        self.params.scopes = scopes;
+        self
+    }
+}
+
+/// Build a complete request from `(client_id, scopes)`
+impl<I: Into<ClientId>> From<(I, ScopeSet)> for DeviceCodeRequest {
+    fn from((client_id, scopes): (I, ScopeSet)) -> Self {
+        Self {
+            params: DeviceCodeParams {
+                client_id: Some(client_id.into()),
+                scopes,
+            },
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+/// Response from a successful [`DeviceCodeRequest`]
+pub struct DeviceCodeResponse {
+    /// Code used to poll for the token, not shown to the user
+    pub device_code: String,
+
+    /// The amount of seconds until the codes expire
+    pub expires_in: u32,
+
+    /// The amount of seconds to wait between polls
+    pub interval: u32,
+
+    /// Code the user enters at [`Self::verification_uri`]
+    pub user_code: String,
+
+    /// Where the user goes to authorize the application, already includes the user code
+    pub verification_uri: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Error, Deserialize)]
+/// Errors returned while polling with a [`DeviceTokenRequest`]
+///
+/// Twitch sends these with a 400 status, so they are told apart by their message
+pub enum DeviceTokenErrorCodes {
+    #[error("authorization_pending: The user has not authorized the application yet")]
+    /// The user has not entered the code yet, poll again after the interval
+    AuthorizationPending,
+
+    #[error("slow_down: Polling too often")]
+    /// Poll less often
+    SlowDown,
+
+    #[error("expired_token: The device code expired")]
+    /// The device code expired before the user authorized the application, a new one
+    /// must be requested
+    ExpiredToken,
+
+    #[error("invalid device code: The device code was not recognized")]
+    /// The device code is not valid for this client, or was already used
+    InvalidDeviceCode,
+
+    #[error("401: Authorization Error")]
+    /// The client id is not valid
+    AuthErrorCode,
+
+    #[error("500: Server Error")]
+    /// Twitch may be experiencing internal errors
+    ServerErrorCode,
+}
+
+impl ErrorCodes for DeviceTokenErrorCodes {
+    fn from_status(codes: FailureStatus<u16>) -> Result<FailureStatus<Self>, FailureStatus<u16>> {
+        let status = match (codes.status, codes.message.as_str()) {
+            (400, "authorization_pending") => Self::AuthorizationPending,
+            (400, "slow_down") => Self::SlowDown,
+            (400, "expired_token") => Self::ExpiredToken,
+            (400, "invalid device code") => Self::InvalidDeviceCode,
+            (401, _) => Self::AuthErrorCode,
+            (500, _) => Self::ServerErrorCode,
+            _ => return Err(codes),
+        };
+
+        Ok(FailureStatus {
+            error: codes.error,
+            status,
+            message: codes.message,
+        })
+    }
+}
+
+#[derive(Debug)]
+/// Do not use directly, instead use [`DeviceTokenRequest`]
+pub struct DeviceTokenParams {
+    client_id: Option<ClientId>,
+    device_code: Option<String>,
+    scopes: ScopeSet,
+}
+
+impl Serialize for DeviceTokenParams {
+    fn serialize<S>(&self, ser: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let mut map = ser.serialize_map(Some(4))?;
+        map.serialize_entry("client_id", &self.client_id)?;
+        map.serialize_entry("scopes", &self.scopes.to_string())?;
+        map.serialize_entry("device_code", &self.device_code)?;
+        map.serialize_entry("grant_type", "urn:ietf:params:oauth:grant-type:device_code")?;
+        map.end()
+    }
+}
+
+impl ParametersExt for DeviceTokenParams {}
+
+#[derive(Debug)]
+/// Request to check whether the user authorized a device code, returning the token once
+/// they have
+pub struct DeviceTokenRequest {
+    params: DeviceTokenParams,
+}
+
+#[cfg_attr(feature = "nightly", doc(spotlight))]
+impl Request for DeviceTokenRequest {
+    const ENDPOINT: &'static str = "https://id.twitch.tv/oauth2/token";
+
+    type Headers = ();
+    type Parameters = DeviceTokenParams;
+    type Body = ();
+
+    type Response = CodeExchangeResponse;
+
+    type ErrorCodes = DeviceTokenErrorCodes;
+
+    const METHOD: reqwest::Method = reqwest::Method::POST;
+
+    fn builder() -> Self {
+        Self {
+            params: DeviceTokenParams {
+                client_id: None,
+                device_code: None,
+                scopes: ScopeSet::new(),
+            },
+        }
+    }
+
+    fn headers(&self) -> &Self::Headers {
+        &()
+    }
+    fn parameters(&self) -> &Self::Parameters {
+        &self.params
+    }
+    fn body(&self) -> &Self::Body {
+        &()
+    }
+
+    fn ready(&self) -> Result<(), RequestError<Self::ErrorCodes>> {
+        if self.params.client_id.is_none() {
+            Err(RequestError::MalformedRequest(String::from(
+                "field client_id must be set",
+            )))
+        } else if self.params.device_code.is_none() {
+            Err(RequestError::MalformedRequest(String::from(
+                "field device_code must be set",
+            )))
+        } else {
+            Ok(())
+        }
+    }
+}
+
+impl DeviceTokenRequest {
+    /// Set the client_id
+    pub fn set_client_id<I: Into<ClientId>>(&mut self, client_id: I) -> &mut Self {
+        self.params.client_id.replace(client_id.into());
+        self
+    }
+
+    /// Set the device code from the [`DeviceCodeResponse`]
+    pub fn set_device_code<S: Into<String>>(&mut self, device_code: S) -> &mut Self {
+        self.params.device_code.replace(device_code.into());
+        self
+    }
+
+    /// Set the scopes, must be the same ones the device code was requested with
+    pub fn set_scopes(&mut self, scopes: ScopeSet) -> &mut Self {
+        self.params.scopes = scopes;
+        self
+    }
+}
+
+/// Build a complete request from `(client_id, device_code, scopes)`
+impl<I, D> From<(I, D, ScopeSet)> for DeviceTokenRequest
+where
+    I: Into<ClientId>,
+    D: Into<String>,
+{
+    fn from((client_id, device_code, scopes): (I, D, ScopeSet)) -> Self {
+        Self {
+            params: DeviceTokenParams {
+                client_id: Some(client_id.into()),
+                device_code: Some(device_code.into()),
+                scopes,
+            },
+        }
+    }
+}
+
+/// Poll for the token until the user authorized `device`, waiting the interval twitch
+/// asked for between polls
+///
+/// The interval grows each time twitch asks to slow down. Returns
+/// [`DeviceTokenErrorCodes::ExpiredToken`] once the device code expires, or any other
+/// error twitch returns.
+pub async fn poll_token<C, I>(
+    client: C,
+    client_id: I,
+    device: &DeviceCodeResponse,
+    scopes: ScopeSet,
+) -> Result<UserAuthToken, RequestError<DeviceTokenErrorCodes>>
+where
+    C: std::borrow::Borrow<reqwest::Client> + Send + Sync,
+    I: Into<ClientId>,
+{
+    let client = client.borrow();
+    let client_id = client_id.into();
+    let resp = poll_with(client_id.clone(), device, scopes, |req| async move {
+        req.make_request(client).await
+    })
+    .await?;
+
+    Ok(resp.into_token(client_id))
+}
+
+/// Poll for the token, sending each [`DeviceTokenRequest`] with `send`
+pub(crate) async fn poll_with<F, Fut>(
+    client_id: ClientId,
+    device: &DeviceCodeResponse,
+    scopes: ScopeSet,
+    send: F,
+) -> Result<CodeExchangeResponse, RequestError<DeviceTokenErrorCodes>>
+where
+    F: Fn(DeviceTokenRequest) -> Fut,
+    Fut: Future<Output = Result<CodeExchangeResponse, RequestError<DeviceTokenErrorCodes>>>,
+{
+    let deadline = Instant::now() + Duration::from_secs(device.expires_in.into());
+    let mut interval = Duration::from_secs(device.interval.into());
+
+    loop {
+        let req = DeviceTokenRequest::from((
+            client_id.clone(),
+            device.device_code.clone(),
+            scopes.clone(),
+        ));
+
+        match send(req).await {
+            Ok(resp) => return Ok(resp),
+            Err(RequestError::KnownErrorStatus(FailureStatus {
+                status: DeviceTokenErrorCodes::AuthorizationPending,
+                ..
+            })) => (),
+            Err(RequestError::KnownErrorStatus(FailureStatus {
+                status: DeviceTokenErrorCodes::SlowDown,
+                ..
+            })) => interval += SLOW_DOWN_STEP,
+            Err(e) => return Err(e),
+        }
+
+        if Instant::now() + interval >= deadline {
+            return Err(RequestError::KnownErrorStatus(FailureStatus {
+                error: None,
+                status: DeviceTokenErrorCodes::ExpiredToken,
+                message: String::from("expired_token"),
+            }));
+        }
+
+        tokio::time::sleep(interval).await;
+    }
+}
@@ -0,0 +1,383 @@
+//! [`Extension`] JWTs signed with the extension secret
+//!
+//! Extension backends authorize requests to twitch with a short lived JWT that they sign
+//! themselves instead of an OAuth token. An [`ExtensionToken`] signs one when it is first
+//! used and again each time it expires, and writes it as the [`Headers`] of a request.
+//!
+//! Only the extension endpoints accept these JWTs, and this crate does not wrap any of
+//! them yet, so the token is for requests that take any [`Headers`] or are written for it.
+//! It is not an [`AppOrUserToken`] and can not be passed to the builders of this crate,
+//! which are bound on that trait or on [`UserAccessToken`].
+//!
+//! [`AppOrUserToken`]: super::AppOrUserToken
+//! [`UserAccessToken`]: super::UserAccessToken
+//!
+//! Requests from the extension frontend carry a JWT signed by twitch with the same
+//! secret, check those with an [`ExtensionVerifier`].
+//!
+//! Requires the `extensions` feature.
+//!
+//! ```no_run
+//! # use twitch_api_rs::auth::extension::*;
+//! # let (client_id, secret, owner_id, header) =
+//! #     (String::new(), String::new(), String::new(), String::new());
+//! let token = ExtensionToken::new(client_id, &secret, owner_id)
+//!     .expect("Extension secret is not base64")
+//!     .channel_id("477906794")
+//!     .send(PubsubTarget::Broadcast);
+//!
+//! let verifier = ExtensionVerifier::new(&secret).expect("Extension secret is not base64");
+//! let claims = verifier
+//!     .verify(header.trim_start_matches("Bearer "))
+//!     .expect("Request was not sent by the extension frontend");
+//! eprintln!("Request from {:?} as {:?}", claims.opaque_user_id, claims.role);
+//! ```
+//!
+//! [`Extension`]: https://dev.twitch.tv/docs/extensions/reference#jwt-schema
+
+use std::fmt;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use jsonwebtoken::{decode, encode, Algorithm, DecodingKey, EncodingKey, Header, Validation};
+use reqwest::RequestBuilder;
+use serde::{Deserialize, Serialize, Serializer};
+use thiserror::Error;
+
+use super::scopes::ScopeSet;
+use super::{AuthToken, ClientId};
+use crate::requests::Headers;
+use crate::values::broadcasters::BroadcasterId;
+use crate::values::users::UserId;
+
+/// How long a signed token is valid for unless set with [`ExtensionToken::lifetime`]
+pub const DEFAULT_LIFETIME: Duration = Duration::from_secs(3 * 60);
+
+/// Tokens are signed again when they expire within this margin
+const RESIGN_MARGIN: Duration = Duration::from_secs(10);
+
+/// The shortest lifetime tokens are signed with, any shorter and every use would sign a
+/// new one
+pub const MIN_LIFETIME: Duration = Duration::from_secs(RESIGN_MARGIN.as_secs() + 1);
+
+/// Returned when an extension JWT could not be created or verified
+#[derive(Debug, Error)]
+pub enum ExtensionError {
+    #[error("Extension secret is not valid base64: {0}")]
+    /// The secret is not the base64 secret shown in the extension settings
+    Secret(jsonwebtoken::errors::Error),
+
+    #[error("Extension JWT is not valid: {0}")]
+    /// The token is malformed, expired or was not signed with the extension secret
+    Jwt(#[from] jsonwebtoken::errors::Error),
+}
+
+/// The role of whoever a token acts as
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ExtensionRole {
+    /// The broadcaster of the channel the extension runs on
+    Broadcaster,
+    /// A moderator of the channel
+    Moderator,
+    /// A viewer of the channel
+    Viewer,
+    /// The extension backend
+    External,
+}
+
+/// A pubsub topic a token may listen or send to
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum PubsubTarget {
+    /// Every viewer of the channel, `broadcast`
+    Broadcast,
+    /// Every channel the extension is installed on, `global`
+    Global,
+    /// One user, `whisper-<opaque_user_id>`
+    ///
+    /// Holds the opaque id of the user as sent in [`ExtensionClaims::opaque_user_id`],
+    /// `U...` for users that shared their id and `A...` for anonymous ones.
+    Whisper(String),
+    /// A target twitch added after this crate was released
+    Other(String),
+}
+
+impl PubsubTarget {
+    fn from_twitch_str(s: &str) -> Self {
+        match s {
+            "broadcast" => Self::Broadcast,
+            "global" => Self::Global,
+            s => match s.strip_prefix("whisper-") {
+                Some(user) => Self::Whisper(user.to_string()),
+                None => Self::Other(s.to_string()),
+            },
+        }
+    }
+}
+
+impl fmt::Display for PubsubTarget {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Broadcast => f.write_str("broadcast"),
+            Self::Global => f.write_str("global"),
+            Self::Whisper(user) => write!(f, "whisper-{}", user),
+            Self::Other(s) => f.write_str(s),
+        }
+    }
+}
+
+impl Serialize for PubsubTarget {
+    fn serialize<S>(&self, ser: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        ser.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for PubsubTarget {
+    fn deserialize<D>(de: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        Ok(Self::from_twitch_str(&String::deserialize(de)?))
+    }
+}
+
+/// Pubsub topics a token may use
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PubsubPerms {
+    /// Topics the token may listen to
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub listen: Vec<PubsubTarget>,
+
+    /// Topics the token may send to
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub send: Vec<PubsubTarget>,
+}
+
+impl PubsubPerms {
+    fn is_empty(&self) -> bool {
+        self.listen.is_empty() && self.send.is_empty()
+    }
+}
+
+/// The claims of an extension JWT
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ExtensionClaims {
+    /// When the token expires, in seconds since the unix epoch
+    pub exp: u64,
+
+    /// Who the token acts as
+    pub role: ExtensionRole,
+
+    /// The twitch id of the user, only sent by the frontend if the user shared it
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub user_id: Option<UserId>,
+
+    /// The channel the extension runs on
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub channel_id: Option<BroadcasterId>,
+
+    /// The pubsub topics the token may use
+    #[serde(default, skip_serializing_if = "PubsubPerms::is_empty")]
+    pub pubsub_perms: PubsubPerms,
+
+    /// Id of the user that stays the same even if they did not share their twitch id,
+    /// only set by the frontend
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub opaque_user_id: Option<String>,
+
+    /// If the user unlinked their twitch id from the extension, only set by the frontend
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub is_unlinked: Option<bool>,
+}
+
+/// A token signed with the extension secret, see the [module level documentation](self)
+///
+/// Clones share the last signed JWT.
+#[derive(Clone)]
+pub struct ExtensionToken {
+    client_id: ClientId,
+    key: EncodingKey,
+    claims: ExtensionClaims,
+    lifetime: Duration,
+    signed: Arc<Mutex<Option<Signed>>>,
+    scopes: ScopeSet,
+}
+
+#[derive(Debug)]
+struct Signed {
+    jwt: String,
+    exp: u64,
+}
+
+impl ExtensionToken {
+    /// Act as the extension backend for `owner_id`, the user that owns the extension
+    ///
+    /// `secret` is the base64 extension secret from the extension settings.
+    pub fn new<I, U>(client_id: I, secret: &str, owner_id: U) -> Result<Self, ExtensionError>
+    where
+        I: Into<ClientId>,
+        U: Into<UserId>,
+    {
+        Ok(Self {
+            client_id: client_id.into(),
+            key: EncodingKey::from_base64_secret(secret).map_err(ExtensionError::Secret)?,
+            claims: ExtensionClaims {
+                exp: 0,
+                role: ExtensionRole::External,
+                user_id: Some(owner_id.into()),
+                channel_id: None,
+                pubsub_perms: PubsubPerms::default(),
+                opaque_user_id: None,
+                is_unlinked: None,
+            },
+            lifetime: DEFAULT_LIFETIME,
+            signed: Arc::new(Mutex::new(None)),
+            scopes: ScopeSet::new(),
+        })
+    }
+
+    /// Act as `role` instead of the extension backend
+    pub fn role(mut self, role: ExtensionRole) -> Self {
+        self.claims.role = role;
+        self.changed()
+    }
+
+    /// Set the user the token acts for
+    pub fn user_id<U: Into<UserId>>(mut self, user_id: U) -> Self {
+        self.claims.user_id = Some(user_id.into());
+        self.changed()
+    }
+
+    /// Set the channel the token acts in
+    pub fn channel_id<B: Into<BroadcasterId>>(mut self, channel_id: B) -> Self {
+        self.claims.channel_id = Some(channel_id.into());
+        self.changed()
+    }
+
+    /// Allow the token to listen to `target`
+    pub fn listen(mut self, target: PubsubTarget) -> Self {
+        self.claims.pubsub_perms.listen.push(target);
+        self.changed()
+    }
+
+    /// Allow the token to send to `target`
+    pub fn send(mut self, target: PubsubTarget) -> Self {
+        self.claims.pubsub_perms.send.push(target);
+        self.changed()
+    }
+
+    /// Sign tokens that are valid for `lifetime`, raised to [`MIN_LIFETIME`] if shorter
+    pub fn lifetime(mut self, lifetime: Duration) -> Self {
+        self.lifetime = lifetime.max(MIN_LIFETIME);
+        self.changed()
+    }
+
+    /// The client id of the extension
+    pub fn client_id(&self) -> &ClientId {
+        &self.client_id
+    }
+
+    /// The claims of the tokens that are signed, `exp` is set when signing
+    pub fn claims(&self) -> &ExtensionClaims {
+        &self.claims
+    }
+
+    /// The signed JWT, signed again if it expired or is about to
+    pub fn jwt(&self) -> String {
+        let now = unix_now();
+        let mut signed = self.signed.lock().unwrap_or_else(|e| e.into_inner());
+
+        match &*signed {
+            Some(s) if s.exp > now + RESIGN_MARGIN.as_secs() => s.jwt.clone(),
+            _ => {
+                let s = self.sign(now);
+                let jwt = s.jwt.clone();
+                *signed = Some(s);
+                jwt
+            }
+        }
+    }
+
+    fn sign(&self, now: u64) -> Signed {
+        let mut claims = self.claims.clone();
+        claims.exp = now + self.lifetime.as_secs();
+
+        let jwt = encode(&Header::new(Algorithm::HS256), &claims, &self.key)
+            .expect("Signing with HS256 does not fail for claims that are valid json");
+
+        Signed {
+            jwt,
+            exp: claims.exp,
+        }
+    }
+
+    /// Drop the JWT that was signed with the old claims, without touching other clones
+    fn changed(mut self) -> Self {
+        self.signed = Arc::new(Mutex::new(None));
+        self
+    }
+}
+
+impl fmt::Debug for ExtensionToken {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ExtensionToken")
+            .field("client_id", &self.client_id)
+            .field("claims", &self.claims)
+            .field("lifetime", &self.lifetime)
+            .finish_non_exhaustive()
+    }
+}
+
+impl Headers for ExtensionToken {
+    fn write_headers(&self, req: RequestBuilder) -> RequestBuilder {
+        req.header("Authorization", format!("Bearer {}", self.jwt()))
+            .header("Client-Id", std::ops::Deref::deref(&self.client_id))
+    }
+}
+
+impl AuthToken for ExtensionToken {
+    fn scopes(&self) -> &ScopeSet {
+        &self.scopes
+    }
+}
+
+/// Checks JWTs sent by the extension frontend, see the [module level documentation](self)
+#[derive(Clone)]
+pub struct ExtensionVerifier {
+    key: DecodingKey,
+    validation: Validation,
+}
+
+impl ExtensionVerifier {
+    /// Verify tokens signed with `secret`, the base64 extension secret
+    pub fn new(secret: &str) -> Result<Self, ExtensionError> {
+        let mut validation = Validation::new(Algorithm::HS256);
+        validation.set_required_spec_claims(&["exp"]);
+
+        Ok(Self {
+            key: DecodingKey::from_base64_secret(secret).map_err(ExtensionError::Secret)?,
+            validation,
+        })
+    }
+
+    /// Check that `token` was signed with the extension secret and has not expired
+    pub fn verify(&self, token: &str) -> Result<ExtensionClaims, ExtensionError> {
+        Ok(decode::<ExtensionClaims>(token, &self.key, &self.validation)?.claims)
+    }
+}
+
+impl fmt::Debug for ExtensionVerifier {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ExtensionVerifier").finish_non_exhaustive()
+    }
+}
+
+fn unix_now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs()
+}
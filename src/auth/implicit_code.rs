@@ -0,0 +1,63 @@
+//! [`Implicit Code`] Flow
+//!
+//! For apps that can not keep a client secret, such as browser extensions or local tools.
+//! Send the user to an [`AuthorizeUrl`], and when twitch redirects them back read the token
+//! from the fragment of the redirect with [`AuthorizeUrl::parse_redirect`]. Tokens from
+//! this flow can not be refreshed.
+//!
+//! ```no_run
+//! # use twitch_api_rs::auth::implicit_code::*;
+//! # use twitch_api_rs::auth::scopes::{Scope, ScopeSet};
+//! # let client_id = String::new();
+//! # let redirect = String::new();
+//! let mut scopes = ScopeSet::new();
+//! scopes.insert(Scope::ChatRead);
+//!
+//! let authorize = AuthorizeUrl::new(ResponseType::Token, client_id, "http://localhost:3000/callback")
+//!     .scopes(scopes);
+//!
+//! eprintln!("Open {} to log in", authorize.build());
+//! // ... twitch redirects the user to `redirect`
+//! let token = authorize.parse_redirect(&redirect).expect("Invalid redirect");
+//! ```
+//!
+//! [`Implicit Code`]: https://dev.twitch.tv/docs/authentication/getting-tokens-oauth/#oauth-implicit-code-flow
+
+use super::callback::callback_params;
+pub use super::callback::{AuthorizeUrl, CallbackError, CsrfState, ResponseType};
+use super::scopes::ScopeSet;
+use super::user_token::UserAuthToken;
+use super::ClientId;
+
+impl AuthorizeUrl {
+    /// Read the token from the url twitch redirected the user to, for urls built with
+    /// [`ResponseType::Token`], see [`parse_redirect`]
+    pub fn parse_redirect(&self, redirect: &str) -> Result<UserAuthToken, CallbackError> {
+        parse_redirect(redirect, self.csrf_state(), self.client_id().clone())
+    }
+}
+
+/// Read the token from the fragment of the url twitch redirected the user to, checking
+/// that it was sent with `state`
+///
+/// The url looks like
+/// `http://localhost:3000/callback#access_token=...&scope=chat%3Aread&state=...&token_type=bearer`.
+/// Browsers do not send the fragment to servers, so it has to be read by a script on the
+/// redirect page or copied by the user.
+pub fn parse_redirect<I: Into<ClientId>>(
+    redirect: &str,
+    state: &CsrfState,
+    client_id: I,
+) -> Result<UserAuthToken, CallbackError> {
+    let params = callback_params(redirect, state, true)?;
+    let get = |name: &str| params.iter().find(|(k, _)| k == name).map(|(_, v)| v);
+
+    let token = get("access_token")
+        .ok_or(CallbackError::Missing("access_token"))?
+        .clone();
+    let scopes: ScopeSet = get("scope")
+        .map(|s| s.split_whitespace().collect())
+        .unwrap_or_default();
+
+    Ok(UserAuthToken::new(token, None, client_id, scopes))
+}
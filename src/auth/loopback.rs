@@ -0,0 +1,283 @@
+//! One shot redirect server on `127.0.0.1` for the [`authorization_code`] flow
+//!
+//! Desktop and command line tools can not receive a redirect on a public url, so twitch
+//! is told to redirect the user back to a server on their own machine instead. The
+//! redirect uri (`http://127.0.0.1:<port>/<path>`) must be registered for the
+//! application, so pick a fixed port with [`LoopbackServer::port`].
+//!
+//! Requires the `loopback` feature.
+//!
+//! ```no_run
+//! # use twitch_api_rs::auth::authorization_code::{AuthorizeUrl, ResponseType};
+//! # use twitch_api_rs::auth::loopback::LoopbackServer;
+//! # use twitch_api_rs::client::Client;
+//! # #[tokio::main]
+//! # async fn main() {
+//! # let (client_id, client_secret) = (String::new(), String::new());
+//! let listener = LoopbackServer::new()
+//!     .port(3000)
+//!     .bind()
+//!     .await
+//!     .expect("Could not listen for the redirect");
+//!
+//! let redirect_uri = listener.redirect_uri();
+//! let authorize = AuthorizeUrl::new(ResponseType::Code, client_id.clone(), redirect_uri.clone());
+//! eprintln!("Open {} to log in", authorize.build());
+//!
+//! let code = listener
+//!     .wait_for_code(authorize.csrf_state())
+//!     .await
+//!     .expect("Did not get a code");
+//!
+//! let client = Client::new(reqwest::Client::new())
+//!     .user_auth(
+//!         client_id.into(),
+//!         client_secret.into(),
+//!         code.code,
+//!         redirect_uri,
+//!     )
+//!     .await;
+//! # }
+//! ```
+//!
+//! [`authorization_code`]: super::authorization_code
+
+use std::net::{Ipv4Addr, SocketAddr};
+use std::time::Duration;
+
+use thiserror::Error;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+
+use super::authorization_code::{parse_callback, AuthorizationCode};
+use super::callback::{CallbackError, CsrfState};
+
+/// Page shown to the user after they were redirected back with a code
+pub const DEFAULT_SUCCESS_PAGE: &str = "<!DOCTYPE html><html><head><title>Logged in</title></head>\
+<body><p>You are logged in and can close this window.</p></body></html>";
+
+/// Largest request head that is read before the request is rejected
+const MAX_REQUEST: usize = 8 * 1024;
+
+/// Returned when no code was received by a [`BoundLoopback`]
+#[derive(Debug, Error)]
+pub enum LoopbackError {
+    #[error("Could not listen for the redirect: {0}")]
+    /// The listener could not be bound or accept connections
+    Io(#[from] std::io::Error),
+
+    #[error("No redirect was received within {0:?}")]
+    /// The user did not finish authorizing before the timeout
+    Timeout(Duration),
+
+    #[error("{0}")]
+    /// The redirect could not be used, for example the state did not match
+    Callback(#[from] CallbackError),
+}
+
+/// Settings for the redirect server, see [`LoopbackServer::bind`] to start it
+#[derive(Debug, Clone)]
+pub struct LoopbackServer {
+    port: u16,
+    path: String,
+    timeout: Duration,
+    read_timeout: Duration,
+    success_page: String,
+}
+
+impl Default for LoopbackServer {
+    fn default() -> Self {
+        Self {
+            port: 0,
+            path: String::from("/"),
+            timeout: Duration::from_secs(5 * 60),
+            read_timeout: Duration::from_secs(10),
+            success_page: String::from(DEFAULT_SUCCESS_PAGE),
+        }
+    }
+}
+
+impl LoopbackServer {
+    /// Listen on a random port for five minutes, on the path `/`
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Listen on `port`, `0` picks any free port
+    pub fn port(mut self, port: u16) -> Self {
+        self.port = port;
+        self
+    }
+
+    /// Only accept the redirect on `path`, other paths get a 404
+    pub fn path<S: Into<String>>(mut self, path: S) -> Self {
+        let path = path.into();
+        self.path = if path.starts_with('/') {
+            path
+        } else {
+            format!("/{}", path)
+        };
+        self
+    }
+
+    /// Give up waiting for the redirect after `timeout`
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Drop connections that did not send a request within `timeout`, ten seconds by
+    /// default
+    ///
+    /// Browsers may open connections ahead of time and never send anything on them, these
+    /// would otherwise block the redirect from being read.
+    pub fn read_timeout(mut self, timeout: Duration) -> Self {
+        self.read_timeout = timeout;
+        self
+    }
+
+    /// Show `html` to the user once they were redirected back
+    pub fn success_page<S: Into<String>>(mut self, html: S) -> Self {
+        self.success_page = html.into();
+        self
+    }
+
+    /// Start listening on `127.0.0.1`
+    pub async fn bind(self) -> Result<BoundLoopback, LoopbackError> {
+        let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, self.port)).await?;
+        let addr = listener.local_addr()?;
+
+        Ok(BoundLoopback {
+            listener,
+            addr,
+            settings: self,
+        })
+    }
+}
+
+/// A listening redirect server, waiting for one redirect
+#[derive(Debug)]
+pub struct BoundLoopback {
+    listener: TcpListener,
+    addr: SocketAddr,
+    settings: LoopbackServer,
+}
+
+impl BoundLoopback {
+    /// The address the server is listening on
+    pub fn local_addr(&self) -> SocketAddr {
+        self.addr
+    }
+
+    /// The redirect uri to send in the authorize url
+    pub fn redirect_uri(&self) -> String {
+        format!(
+            "http://127.0.0.1:{}{}",
+            self.addr.port(),
+            self.settings.path
+        )
+    }
+
+    /// Wait for the user to be redirected back, and read the code after checking that it
+    /// was sent with `state`
+    ///
+    /// The server stops after the first redirect to its path, whether or not it carried a
+    /// code.
+    pub async fn wait_for_code(
+        self,
+        state: &CsrfState,
+    ) -> Result<AuthorizationCode, LoopbackError> {
+        let timeout = self.settings.timeout;
+
+        match tokio::time::timeout(timeout, self.accept(state)).await {
+            Ok(result) => result,
+            Err(_) => Err(LoopbackError::Timeout(timeout)),
+        }
+    }
+
+    async fn accept(&self, state: &CsrfState) -> Result<AuthorizationCode, LoopbackError> {
+        loop {
+            let (mut stream, _) = self.listener.accept().await?;
+
+            let read = tokio::time::timeout(self.settings.read_timeout, read_target(&mut stream));
+            let target = match read.await {
+                Ok(Ok(Some(target))) => target,
+                Ok(Ok(None)) => {
+                    respond(&mut stream, "400 Bad Request", "Bad request").await;
+                    continue;
+                }
+                Ok(Err(e)) => {
+                    log::debug!("Could not read request to loopback server: {}", e);
+                    continue;
+                }
+                Err(_) => {
+                    log::debug!("Dropped loopback connection that sent no request");
+                    continue;
+                }
+            };
+
+            let path = target.split('?').next().unwrap_or_default();
+            if path != self.settings.path {
+                respond(&mut stream, "404 Not Found", "Not found").await;
+                continue;
+            }
+
+            let redirect = format!("http://{}{}", self.addr, target);
+            return match parse_callback(&redirect, state) {
+                Ok(code) => {
+                    respond(&mut stream, "200 OK", &self.settings.success_page).await;
+                    Ok(code)
+                }
+                Err(e) => {
+                    respond(&mut stream, "400 Bad Request", &escape(&e.to_string())).await;
+                    Err(e.into())
+                }
+            };
+        }
+    }
+}
+
+/// Read the head of a request and get its target, `None` if it is not a GET request
+async fn read_target(stream: &mut TcpStream) -> std::io::Result<Option<String>> {
+    let mut head = Vec::new();
+    let mut buf = [0u8; 1024];
+
+    while !head.windows(4).any(|w| w == b"\r\n\r\n") {
+        let n = stream.read(&mut buf).await?;
+        if n == 0 || head.len() + n > MAX_REQUEST {
+            return Ok(None);
+        }
+        head.extend_from_slice(&buf[..n]);
+    }
+
+    let head = String::from_utf8_lossy(&head);
+    let mut parts = head.lines().next().unwrap_or_default().split(' ');
+
+    Ok(match (parts.next(), parts.next()) {
+        (Some("GET"), Some(target)) => Some(target.to_string()),
+        _ => None,
+    })
+}
+
+/// Send a html page and close the connection, errors are only logged as the redirect
+/// was already read
+async fn respond(stream: &mut TcpStream, status: &str, body: &str) {
+    let resp = format!(
+        "HTTP/1.1 {}\r\nContent-Type: text/html; charset=utf-8\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        status,
+        body.len(),
+        body
+    );
+
+    if let Err(e) = stream.write_all(resp.as_bytes()).await {
+        log::debug!("Could not respond to redirect: {}", e);
+    }
+    let _ = stream.shutdown().await;
+}
+
+/// Escape text so it can be shown in a html page
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
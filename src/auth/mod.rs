@@ -4,7 +4,11 @@
 //!
 //! [`Twitch Auth Documentation`]: https://dev.twitch.tv/docs/authentication
 
+pub mod callback;
 pub mod scopes;
+pub mod store;
+pub mod user_token;
+pub mod validate;
 
 /// Represents a authorization token of some type that can be sent as a header to a
 /// twitch endpoint.
@@ -13,6 +17,29 @@ pub trait AuthToken: crate::requests::Headers + Clone {
     fn scopes(&self) -> &scopes::ScopeSet;
 }
 
+/// An app access token, issued to the application itself with the
+/// [`client_credentials`] flow
+pub trait AppToken: AppOrUserToken {}
+
+/// A user access token, that acts on behalf of the user who authorized it
+///
+/// Endpoints that read or change data of a user are bound on this trait, so an
+/// [`AppToken`] given to them fails to compile instead of being rejected by twitch.
+///
+/// ```compile_fail
+/// # use twitch_api_rs::auth::{client_credentials::ClientAuthToken, UserAccessToken};
+/// fn for_user<T: UserAccessToken>(token: T) {}
+///
+/// for_user(ClientAuthToken::new(String::from("token"), "client_id"));
+/// ```
+pub trait UserAccessToken: AppOrUserToken {}
+
+/// An OAuth token of either kind, accepted by endpoints that do not act for a user
+///
+/// Every [`AppToken`] and [`UserAccessToken`] must also implement this trait, tokens
+/// like extension JWTs that these endpoints reject do not.
+pub trait AppOrUserToken: AuthToken {}
+
 use reqwest::RequestBuilder;
 use std::rc::Rc;
 use std::sync::Arc;
@@ -35,6 +62,10 @@ where
     }
 }
 
+impl<A: AppToken> AppToken for Arc<A> {}
+impl<A: UserAccessToken> UserAccessToken for Arc<A> {}
+impl<A: AppOrUserToken> AppOrUserToken for Arc<A> {}
+
 impl<H> crate::requests::Headers for Rc<H>
 where
     H: crate::requests::Headers,
@@ -53,12 +84,16 @@ where
     }
 }
 
+impl<A: AppToken> AppToken for Rc<A> {}
+impl<A: UserAccessToken> UserAccessToken for Rc<A> {}
+impl<A: AppOrUserToken> AppOrUserToken for Rc<A> {}
+
 use crate::values::FieldValue;
 use crate::{field_wrapper_name, from_inner, quick_deref_into};
 use serde::{Deserialize, Serialize};
 
 #[repr(transparent)]
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 #[serde(transparent)]
 /// Represents a [`crate::auth::client_credentials`] id.  
 /// See [`Twitch Auth Guide`] for more
@@ -79,15 +114,17 @@ quick_deref_into![(ClientId, String), (ClientSecret, String)];
 from_inner![(ClientId, String), (ClientSecret, String)];
 field_wrapper_name![ClientId => "client_id", ClientSecret => "client_secret"];
 
-/// [`Implicit Code`] Flow
-///
-/// [`Implicit Code`]: https://dev.twitch.tv/docs/authentication/getting-tokens-oauth/#oauth-implicit-code-flow
-pub mod implicit_code {}
-
-/// [`Authorization Code`] Flow
-///
-/// [`Authorization Code`]: https://dev.twitch.tv/docs/authentication/getting-tokens-oauth/#oauth-implicit-code-flow
-pub mod authorization_code {}
+pub mod authorization_code;
+pub mod device_code;
+#[cfg(feature = "extensions")]
+pub mod extension;
+pub mod implicit_code;
+#[cfg(feature = "loopback")]
+pub mod loopback;
+#[cfg(feature = "oidc")]
+pub mod oidc;
+pub mod refresh_token;
+pub mod revoke;
 
 /// [`Client Credentials`] Flow
 ///
@@ -130,6 +167,7 @@ pub mod client_credentials {
     use crate::requests::*; // TODO: Replace with internal prelude
     use reqwest::RequestBuilder;
     use serde::{ser::SerializeMap, Deserialize, Serialize, Serializer};
+    use std::time::{Duration, Instant};
 
     #[derive(Debug)]
     #[doc(hidden)]
@@ -137,7 +175,7 @@ pub mod client_credentials {
     pub struct ClientAuthRequestParams {
         client_id: Option<ClientId>,
         client_secret: Option<ClientSecret>,
-        scopes: Vec<String>, // TODO change to list of Scope Enum items or maybe bitset that has display trait and named bits
+        scopes: ScopeSet,
     }
 
     impl ParametersExt for ClientAuthRequestParams {}
@@ -158,12 +196,14 @@ pub mod client_credentials {
         where
             S: Serializer,
         {
-            let mut map = ser.serialize_map(Some(if self.scopes.len() > 0 { 4 } else { 3 }))?;
+            let mut map = ser.serialize_map(Some(if !self.scopes.is_empty() { 4 } else { 3 }))?;
             map.serialize_entry("client_id", self.client_id.as_ref().unwrap())?;
             map.serialize_entry("client_secret", self.client_secret.as_ref().unwrap())?;
             map.serialize_entry("grant_type", "client_credentials")?;
 
-            // TODO Serialize vec as space separated list
+            if !self.scopes.is_empty() {
+                map.serialize_entry("scope", &self.scopes.to_string())?;
+            }
 
             map.end()
         }
@@ -188,7 +228,7 @@ pub mod client_credentials {
                 params: ClientAuthRequestParams {
                     client_id: None,
                     client_secret: None,
-                    scopes: Vec::new(),
+                    scopes: ScopeSet::new(),
                 },
             }
         }
@@ -230,6 +270,12 @@ pub mod client_credentials {
             self.params.client_secret.replace(client_secret.into());
             self
         }
+
+        /// Set the scopes to request for the app access token
+        pub fn set_scopes(&mut self, scopes: ScopeSet) -> &mut Self {
+            self.params.scopes = scopes;
+            self
+        }
     }
 
     /// Build a complete request from `(client_id, client_secret)`
@@ -243,7 +289,7 @@ pub mod client_credentials {
                 params: ClientAuthRequestParams {
                     client_id: Some(client_id.into()),
                     client_secret: Some(client_secret.into()),
-                    scopes: vec![],
+                    scopes: ScopeSet::new(),
                 },
             }
         }
@@ -262,9 +308,9 @@ pub mod client_credentials {
         // token_type: String // Always bearer
     }
 
-    impl Into<(String, u32)> for ClientAuthResponse {
-        fn into(self) -> (String, u32) {
-            (self.access_token, self.expires_in)
+    impl From<ClientAuthResponse> for (String, u32) {
+        fn from(resp: ClientAuthResponse) -> Self {
+            (resp.access_token, resp.expires_in)
         }
     }
 
@@ -277,6 +323,8 @@ pub mod client_credentials {
         scopes: ScopeSet,
         pub token: String,
         pub client_id: ClientId,
+        issued_at: Instant,
+        expires_at: Option<Instant>,
     }
 
     impl ClientAuthToken {
@@ -285,22 +333,66 @@ pub mod client_credentials {
         where
             C: Into<ClientId>,
         {
-            Self {
-                // Fill with empty scopes item as scopes only apply to OAuth tokens
-                scopes: ScopeSet::new(),
-                token: auth_response.access_token,
-                client_id: client_id.into(),
-            }
+            let expires_in = Duration::from_secs(auth_response.expires_in.into());
+
+            // Fill with empty scopes item as scopes only apply to OAuth tokens
+            Self::new(auth_response.access_token, client_id).with_expiry(expires_in)
         }
 
-        /// Create a new ClientAuthToken
+        /// Create a new ClientAuthToken that is not known to expire
         pub fn new(token: String, id: impl Into<ClientId>) -> Self {
             Self {
                 scopes: ScopeSet::new(),
                 token,
                 client_id: id.into(),
+                issued_at: Instant::now(),
+                expires_at: None,
             }
         }
+
+        /// Set when the token was issued, for tokens that were received earlier
+        pub(crate) fn issued(mut self, issued_at: Instant) -> Self {
+            self.issued_at = issued_at;
+            self
+        }
+
+        /// Set the token to expire `expires_in` after it was issued
+        pub fn with_expiry(mut self, expires_in: Duration) -> Self {
+            self.expires_at = Some(self.issued_at + expires_in);
+            self
+        }
+
+        /// Set the scopes of the token, see [`crate::auth::validate`] to find them
+        pub fn with_scopes(mut self, scopes: ScopeSet) -> Self {
+            self.scopes = scopes;
+            self
+        }
+
+        /// When the token was received
+        pub fn issued_at(&self) -> Instant {
+            self.issued_at
+        }
+
+        /// When the token expires, `None` if not known
+        pub fn expires_at(&self) -> Option<Instant> {
+            self.expires_at
+        }
+
+        /// How long until the token expires, zero if it already has, `None` if not known
+        pub fn expires_in(&self) -> Option<Duration> {
+            self.expires_at
+                .map(|at| at.saturating_duration_since(Instant::now()))
+        }
+
+        /// If the token has expired, tokens that are not known to expire never do
+        pub fn is_expired(&self) -> bool {
+            self.expires_within(Duration::ZERO)
+        }
+
+        /// If the token expires within `margin`
+        pub fn expires_within(&self, margin: Duration) -> bool {
+            matches!(self.expires_in(), Some(left) if left <= margin)
+        }
     }
 
     impl Headers for ClientAuthToken {
@@ -315,4 +407,7 @@ pub mod client_credentials {
             &self.scopes
         }
     }
+
+    impl super::AppToken for ClientAuthToken {}
+    impl super::AppOrUserToken for ClientAuthToken {}
 }
@@ -0,0 +1,407 @@
+//! [`OpenID Connect`] login
+//!
+//! Ask for an id token with [`AuthorizeUrl::openid`], then verify the `id_token` returned
+//! with the code exchange against the keys twitch publishes, see [`JwksRequest`] and
+//! [`verify_id_token`]. The claims that were asked for are in the verified
+//! [`IdTokenClaims`], or can be requested with a [`UserInfoRequest`].
+//!
+//! Requires the `oidc` feature.
+//!
+//! ```no_run
+//! # use twitch_api_rs::auth::authorization_code::*;
+//! # use twitch_api_rs::auth::oidc::*;
+//! # use twitch_api_rs::requests::Request;
+//! # #[tokio::main]
+//! # async fn main() {
+//! # let (client_id, client_secret) = (String::new(), String::new());
+//! # let redirect = String::new();
+//! let client = reqwest::Client::new();
+//! let nonce = CsrfState::random();
+//!
+//! let authorize = AuthorizeUrl::new(
+//!     ResponseType::Code,
+//!     client_id.clone(),
+//!     "http://localhost:3000/callback",
+//! )
+//!     .openid(
+//!         &nonce,
+//!         &ClaimsRequest::new()
+//!             .id_token(Claim::PreferredUsername)
+//!             .id_token(Claim::Email),
+//!     );
+//!
+//! eprintln!("Open {} to log in", authorize.build());
+//! let code = parse_callback(&redirect, authorize.csrf_state()).expect("Invalid redirect");
+//!
+//! let resp = CodeExchangeRequest::from((
+//!     client_id.clone(),
+//!     client_secret,
+//!     code.code,
+//!     "http://localhost:3000/callback",
+//! ))
+//! .make_request(&client)
+//! .await
+//! .expect("Could not exchange code");
+//!
+//! let keys = JwksRequest::builder()
+//!     .make_request(&client)
+//!     .await
+//!     .expect("Could not get keys");
+//!
+//! let claims = verify_id_token(
+//!     resp.id_token.as_deref().expect("No id token"),
+//!     &keys,
+//!     &client_id,
+//!     &nonce,
+//! )
+//! .expect("Id token is not valid");
+//!
+//! eprintln!("Logged in as {:?}", claims.preferred_username);
+//! # }
+//! ```
+//!
+//! [`OpenID Connect`]: https://dev.twitch.tv/docs/authentication/getting-tokens-oidc
+
+use jsonwebtoken::{decode, decode_header, Algorithm, DecodingKey, Validation};
+use serde::{ser::SerializeMap, Deserialize, Serialize, Serializer};
+use thiserror::Error;
+
+pub use jsonwebtoken::jwk::{Jwk, JwkSet};
+
+use super::callback::AuthorizeUrl;
+use super::callback::CsrfState;
+use super::scopes::Scope;
+use super::UserAccessToken;
+use crate::requests::*;
+
+/// The issuer of twitch id tokens
+pub const ISSUER: &str = "https://id.twitch.tv/oauth2";
+
+/// A claim about the user that can be requested
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Claim {
+    /// The email address of the user, requires the `user:read:email` scope
+    Email,
+    /// If twitch verified the email address of the user
+    EmailVerified,
+    /// The url of the profile image of the user
+    Picture,
+    /// The display name of the user
+    PreferredUsername,
+    /// When the profile of the user was last changed
+    UpdatedAt,
+}
+
+impl Claim {
+    /// The name twitch uses for the claim
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Email => "email",
+            Self::EmailVerified => "email_verified",
+            Self::Picture => "picture",
+            Self::PreferredUsername => "preferred_username",
+            Self::UpdatedAt => "updated_at",
+        }
+    }
+}
+
+/// Claims to include in the id token and the userinfo response, sent as the `claims`
+/// parameter of the authorize url
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ClaimsRequest {
+    id_token: Vec<Claim>,
+    userinfo: Vec<Claim>,
+}
+
+impl ClaimsRequest {
+    /// Request no claims
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Include `claim` in the id token
+    pub fn id_token(mut self, claim: Claim) -> Self {
+        if !self.id_token.contains(&claim) {
+            self.id_token.push(claim);
+        }
+        self
+    }
+
+    /// Include `claim` in the response to a [`UserInfoRequest`]
+    pub fn userinfo(mut self, claim: Claim) -> Self {
+        if !self.userinfo.contains(&claim) {
+            self.userinfo.push(claim);
+        }
+        self
+    }
+}
+
+/// The claims of one place as a map of claim names to `null`
+struct ClaimMap<'a>(&'a [Claim]);
+
+impl Serialize for ClaimMap<'_> {
+    fn serialize<S>(&self, ser: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let mut map = ser.serialize_map(Some(self.0.len()))?;
+        for claim in self.0 {
+            map.serialize_entry(claim.as_str(), &())?;
+        }
+        map.end()
+    }
+}
+
+impl Serialize for ClaimsRequest {
+    fn serialize<S>(&self, ser: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let mut map = ser.serialize_map(None)?;
+        if !self.id_token.is_empty() {
+            map.serialize_entry("id_token", &ClaimMap(&self.id_token))?;
+        }
+        if !self.userinfo.is_empty() {
+            map.serialize_entry("userinfo", &ClaimMap(&self.userinfo))?;
+        }
+        map.end()
+    }
+}
+
+impl AuthorizeUrl {
+    /// Also ask for an id token, that will be sent with `nonce` and include `claims`
+    ///
+    /// Adds the `openid` scope to the scopes that were set before. With
+    /// [`ResponseType::Token`] the id token is sent with the redirect, otherwise it is
+    /// returned by the code exchange.
+    ///
+    /// [`ResponseType::Token`]: super::authorization_code::ResponseType::Token
+    pub fn openid(mut self, nonce: &CsrfState, claims: &ClaimsRequest) -> Self {
+        self.scopes.insert(Scope::OpenId);
+        self.id_token = true;
+
+        self.extra.retain(|(k, _)| *k != "nonce" && *k != "claims");
+        self.extra.push(("nonce", nonce.secret().to_string()));
+        self.extra.push((
+            "claims",
+            serde_json::to_string(claims).expect("Claims are always valid json"),
+        ));
+        self
+    }
+}
+
+/// Returned when an id token could not be verified
+#[derive(Debug, Error)]
+pub enum OidcError {
+    #[error("Id token is not valid: {0}")]
+    /// The token is malformed, expired, for another client or its signature is wrong
+    Jwt(#[from] jsonwebtoken::errors::Error),
+
+    #[error("Id token was signed with unknown key {0:?}")]
+    /// No key in the key set has the id the token was signed with
+    UnknownKey(Option<String>),
+
+    #[error("Id token was not sent with the expected nonce")]
+    /// The nonce was missing or different, the token may have been replayed
+    NonceMismatch,
+}
+
+/// The claims of a verified id token
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct IdTokenClaims {
+    /// Who issued the token, always [`ISSUER`]
+    pub iss: String,
+
+    /// The id of the user
+    pub sub: String,
+
+    /// The client the token was issued to
+    pub aud: String,
+
+    /// When the token expires, in seconds since the unix epoch
+    pub exp: u64,
+
+    /// When the token was issued, in seconds since the unix epoch
+    pub iat: u64,
+
+    /// The nonce sent in the authorize url
+    #[serde(default)]
+    pub nonce: Option<String>,
+
+    /// See [`Claim::Email`]
+    #[serde(default)]
+    pub email: Option<String>,
+
+    /// See [`Claim::EmailVerified`]
+    #[serde(default)]
+    pub email_verified: Option<bool>,
+
+    /// See [`Claim::Picture`]
+    #[serde(default)]
+    pub picture: Option<String>,
+
+    /// See [`Claim::PreferredUsername`]
+    #[serde(default)]
+    pub preferred_username: Option<String>,
+
+    /// See [`Claim::UpdatedAt`]
+    #[serde(default)]
+    pub updated_at: Option<String>,
+}
+
+/// Check the signature of `id_token` against `keys`, and that it was issued by twitch to
+/// `client_id` with `nonce` and has not expired
+pub fn verify_id_token(
+    id_token: &str,
+    keys: &JwkSet,
+    client_id: &str,
+    nonce: &CsrfState,
+) -> Result<IdTokenClaims, OidcError> {
+    let header = decode_header(id_token)?;
+
+    let jwk = match &header.kid {
+        Some(kid) => keys.find(kid),
+        None if keys.keys.len() == 1 => keys.keys.first(),
+        None => None,
+    }
+    .ok_or(OidcError::UnknownKey(header.kid))?;
+
+    let mut validation = Validation::new(Algorithm::RS256);
+    validation.set_audience(&[client_id]);
+    validation.set_issuer(&[ISSUER]);
+    validation.set_required_spec_claims(&["exp", "iss", "aud", "sub"]);
+
+    let claims =
+        decode::<IdTokenClaims>(id_token, &DecodingKey::from_jwk(jwk)?, &validation)?.claims;
+
+    match &claims.nonce {
+        Some(sent) if nonce.verify(sent).is_ok() => Ok(claims),
+        _ => Err(OidcError::NonceMismatch),
+    }
+}
+
+/// Request for the keys twitch signs id tokens with
+#[derive(Debug)]
+pub struct JwksRequest {}
+
+#[cfg_attr(feature = "nightly", doc(spotlight))]
+impl Request for JwksRequest {
+    const ENDPOINT: &'static str = "https://id.twitch.tv/oauth2/keys";
+
+    type Headers = ();
+    type Parameters = ();
+    type Body = ();
+
+    type Response = JwkSet;
+
+    type ErrorCodes = CommonResponseCodes;
+
+    const METHOD: reqwest::Method = reqwest::Method::GET;
+
+    fn builder() -> Self {
+        Self {}
+    }
+
+    fn headers(&self) -> &Self::Headers {
+        &()
+    }
+    fn parameters(&self) -> &Self::Parameters {
+        &()
+    }
+    fn body(&self) -> &Self::Body {
+        &()
+    }
+
+    fn ready(&self) -> Result<(), RequestError<Self::ErrorCodes>> {
+        Ok(())
+    }
+}
+
+/// Request for the claims about the user that were asked for with
+/// [`ClaimsRequest::userinfo`]
+#[derive(Debug)]
+pub struct UserInfoRequest<A>
+where
+    A: UserAccessToken,
+{
+    auth: Option<A>,
+}
+
+#[cfg_attr(feature = "nightly", doc(spotlight))]
+impl<A> Request for UserInfoRequest<A>
+where
+    A: UserAccessToken + Send,
+{
+    const ENDPOINT: &'static str = "https://id.twitch.tv/oauth2/userinfo";
+
+    type Headers = A;
+    type Parameters = ();
+    type Body = ();
+
+    type Response = UserInfo;
+
+    type ErrorCodes = CommonResponseCodes;
+
+    const METHOD: reqwest::Method = reqwest::Method::GET;
+
+    fn builder() -> Self {
+        Self { auth: None }
+    }
+
+    fn headers(&self) -> &Self::Headers {
+        self.auth.as_ref().unwrap()
+    }
+    fn parameters(&self) -> &Self::Parameters {
+        &()
+    }
+    fn body(&self) -> &Self::Body {
+        &()
+    }
+
+    fn ready(&self) -> Result<(), RequestError<Self::ErrorCodes>> {
+        if self.auth.is_none() {
+            Err(RequestError::MissingAuth)
+        } else {
+            Ok(())
+        }
+    }
+}
+
+impl<A: UserAccessToken> UserInfoRequest<A> {
+    /// Set the user token that was issued with the `openid` scope
+    pub fn set_auth(&mut self, auth: A) -> &mut Self {
+        self.auth.replace(auth);
+        self
+    }
+}
+
+/// Response from a successful [`UserInfoRequest`]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct UserInfo {
+    /// The client the token was issued to
+    #[serde(default)]
+    pub aud: Option<String>,
+
+    /// The id of the user
+    pub sub: String,
+
+    /// See [`Claim::Email`]
+    #[serde(default)]
+    pub email: Option<String>,
+
+    /// See [`Claim::EmailVerified`]
+    #[serde(default)]
+    pub email_verified: Option<bool>,
+
+    /// See [`Claim::Picture`]
+    #[serde(default)]
+    pub picture: Option<String>,
+
+    /// See [`Claim::PreferredUsername`]
+    #[serde(default)]
+    pub preferred_username: Option<String>,
+
+    /// See [`Claim::UpdatedAt`]
+    #[serde(default)]
+    pub updated_at: Option<String>,
+}
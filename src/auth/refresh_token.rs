@@ -0,0 +1,264 @@
+//! [`Refreshing`] user access tokens
+//!
+//! User access tokens expire, and can be renewed with the refresh token they were issued
+//! with. Twitch may return a new refresh token each time, so always keep the one in the
+//! token returned by [`RefreshTokenResponse::into_token`].
+//!
+//! ```no_run
+//! # use twitch_api_rs::auth::refresh_token::*;
+//! # use twitch_api_rs::auth::scopes::ScopeSet;
+//! # use twitch_api_rs::auth::user_token::UserAuthToken;
+//! # use twitch_api_rs::requests::{Request, RequestError};
+//! # #[tokio::main]
+//! # async fn main() {
+//! # let (access, refresh, client_id, client_secret) =
+//! #     (String::new(), String::new(), String::new(), String::new());
+//! let current = UserAuthToken::new(access, Some(refresh), client_id, ScopeSet::new());
+//!
+//! let token = match RefreshTokenRequest::from_token(&current, client_secret) {
+//!     Some(req) => match req.make_request(&reqwest::Client::new()).await {
+//!         Ok(resp) => resp.into_token(&current),
+//!         Err(RequestError::KnownErrorStatus(e))
+//!             if e.status == RefreshTokenErrorCodes::InvalidRefreshToken =>
+//!         {
+//!             panic!("User must authorize the application again")
+//!         }
+//!         Err(e) => panic!("Could not refresh token: {}", e),
+//!     },
+//!     None => panic!("Token can not be refreshed"),
+//! };
+//! # }
+//! ```
+//!
+//! [`Refreshing`]: https://dev.twitch.tv/docs/authentication/refresh-tokens
+
+use serde::{ser::SerializeMap, Deserialize, Serialize, Serializer};
+use thiserror::Error;
+
+use super::scopes::ScopeSet;
+use super::user_token::UserAuthToken;
+use super::{AuthToken, ClientId, ClientSecret};
+use crate::requests::*;
+
+#[derive(Debug)]
+/// Do not use directly, instead use [`RefreshTokenRequest`]
+pub struct RefreshTokenParams {
+    client_id: Option<ClientId>,
+    client_secret: Option<ClientSecret>,
+    refresh_token: Option<String>,
+}
+
+impl Serialize for RefreshTokenParams {
+    fn serialize<S>(&self, ser: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let mut map = ser.serialize_map(None)?;
+        map.serialize_entry("client_id", &self.client_id)?;
+        if let Some(secret) = &self.client_secret {
+            map.serialize_entry("client_secret", secret)?;
+        }
+        map.serialize_entry("grant_type", "refresh_token")?;
+        map.serialize_entry("refresh_token", &self.refresh_token)?;
+        map.end()
+    }
+}
+
+impl ParametersExt for RefreshTokenParams {}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Error, Deserialize)]
+/// Errors returned when refreshing a token
+pub enum RefreshTokenErrorCodes {
+    #[error("400: Invalid refresh token")]
+    /// The refresh token was revoked, already used or never valid. The user must authorize
+    /// the application again
+    InvalidRefreshToken,
+
+    #[error("400: Malformed Request")]
+    /// The request did not conform to what the endpoint was expecting
+    BadRequestCode,
+
+    #[error("401: Authorization Error")]
+    /// The client id or secret was not valid
+    AuthErrorCode,
+
+    #[error("500: Server Error")]
+    /// Twitch may be experiencing internal errors
+    ServerErrorCode,
+}
+
+impl ErrorCodes for RefreshTokenErrorCodes {
+    fn from_status(codes: FailureStatus<u16>) -> Result<FailureStatus<Self>, FailureStatus<u16>> {
+        let status = match codes.status {
+            400 if codes.message.eq_ignore_ascii_case("invalid refresh token") => {
+                Self::InvalidRefreshToken
+            }
+            400 => Self::BadRequestCode,
+            401 => Self::AuthErrorCode,
+            500 => Self::ServerErrorCode,
+            _ => return Err(codes),
+        };
+
+        Ok(FailureStatus {
+            error: codes.error,
+            status,
+            message: codes.message,
+        })
+    }
+}
+
+#[derive(Debug)]
+/// Request to get a new access token with a refresh token
+pub struct RefreshTokenRequest {
+    params: RefreshTokenParams,
+}
+
+#[cfg_attr(feature = "nightly", doc(spotlight))]
+impl Request for RefreshTokenRequest {
+    const ENDPOINT: &'static str = "https://id.twitch.tv/oauth2/token";
+
+    type Headers = ();
+    type Parameters = RefreshTokenParams;
+    type Body = ();
+
+    type Response = RefreshTokenResponse;
+
+    type ErrorCodes = RefreshTokenErrorCodes;
+
+    const METHOD: reqwest::Method = reqwest::Method::POST;
+
+    fn builder() -> Self {
+        Self {
+            params: RefreshTokenParams {
+                client_id: None,
+                client_secret: None,
+                refresh_token: None,
+            },
+        }
+    }
+
+    fn headers(&self) -> &Self::Headers {
+        &()
+    }
+    fn parameters(&self) -> &Self::Parameters {
+        &self.params
+    }
+    fn body(&self) -> &Self::Body {
+        &()
+    }
+
+    fn ready(&self) -> Result<(), RequestError<Self::ErrorCodes>> {
+        if self.params.client_id.is_none() {
+            Err(RequestError::MalformedRequest(String::from(
+                "field client_id must be set",
+            )))
+        } else if self.params.refresh_token.is_none() {
+            Err(RequestError::MalformedRequest(String::from(
+                "field refresh_token must be set",
+            )))
+        } else {
+            Ok(())
+        }
+    }
+}
+
+impl RefreshTokenRequest {
+    /// Refresh `token` for the client it was issued to, or `None` if it has no refresh
+    /// token
+    pub fn from_token<S: Into<ClientSecret>>(token: &UserAuthToken, secret: S) -> Option<Self> {
+        let refresh_token = token.refresh_token.clone()?;
+        Some(Self {
+            params: RefreshTokenParams {
+                client_id: Some(token.client_id.clone()),
+                client_secret: Some(secret.into()),
+                refresh_token: Some(refresh_token),
+            },
+        })
+    }
+
+    /// Set the client_id
+    pub fn set_client_id<I: Into<ClientId>>(&mut self, client_id: I) -> &mut Self {
+        self.params.client_id.replace(client_id.into());
+        self
+    }
+
+    /// Set the client_secret, not needed for public clients
+    pub fn set_client_secret<S: Into<ClientSecret>>(&mut self, client_secret: S) -> &mut Self {
+        self.params.client_secret.replace(client_secret.into());
+        self
+    }
+
+    /// Set the refresh token
+    pub fn set_refresh_token<S: Into<String>>(&mut self, refresh_token: S) -> &mut Self {
+        self.params.refresh_token.replace(refresh_token.into());
+        self
+    }
+}
+
+/// Build a complete request from `(client_id, client_secret, refresh_token)`
+impl<I, S, R> From<(I, S, R)> for RefreshTokenRequest
+where
+    I: Into<ClientId>,
+    S: Into<ClientSecret>,
+    R: Into<String>,
+{
+    fn from((client_id, client_secret, refresh_token): (I, S, R)) -> Self {
+        Self {
+            params: RefreshTokenParams {
+                client_id: Some(client_id.into()),
+                client_secret: Some(client_secret.into()),
+                refresh_token: Some(refresh_token.into()),
+            },
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+/// Response from a successful [`RefreshTokenRequest`]
+pub struct RefreshTokenResponse {
+    /// The new access token
+    pub access_token: String,
+
+    /// The refresh token to use next time, may be different from the one that was sent
+    pub refresh_token: String,
+
+    /// The amount of seconds until the new token expires
+    #[serde(default)]
+    pub expires_in: Option<u32>,
+
+    /// The scopes of the token, as twitch names them
+    #[serde(default)]
+    pub scope: Vec<String>,
+
+    /// Always `bearer`
+    pub token_type: String,
+}
+
+impl RefreshTokenResponse {
+    /// The scopes of the new token
+    pub fn scopes(&self) -> ScopeSet {
+        self.scope.iter().map(String::as_str).collect()
+    }
+
+    /// Replace the access and refresh token of `current`, keeping the client and user it
+    /// was issued for
+    ///
+    /// Keeps the scopes of `current` if twitch did not list any.
+    pub fn into_token(self, current: &UserAuthToken) -> UserAuthToken {
+        let scopes = if self.scope.is_empty() {
+            current.scopes().clone()
+        } else {
+            self.scopes()
+        };
+
+        let mut token = UserAuthToken::new(
+            self.access_token,
+            Some(self.refresh_token),
+            current.client_id.clone(),
+            scopes,
+        );
+        token.user_id = current.user_id.clone();
+        token.login = current.login.clone();
+        token
+    }
+}
@@ -0,0 +1,193 @@
+//! [`Revoking`] access tokens
+//!
+//! Revoke a token when the user logs out or when credentials are rotated, so it can not
+//! be used again. [`crate::client::Client::revoke`] also removes the token from the token
+//! store of the client.
+//!
+//! ```no_run
+//! # use twitch_api_rs::auth::scopes::ScopeSet;
+//! # use twitch_api_rs::auth::user_token::UserAuthToken;
+//! # #[tokio::main]
+//! # async fn main() {
+//! # let (access, client_id) = (String::new(), String::new());
+//! let token = UserAuthToken::new(access, None, client_id, ScopeSet::new());
+//!
+//! token
+//!     .revoke(&reqwest::Client::new())
+//!     .await
+//!     .expect("Could not revoke token");
+//! # }
+//! ```
+//!
+//! [`Revoking`]: https://dev.twitch.tv/docs/authentication/revoke-tokens
+
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use super::client_credentials::ClientAuthToken;
+use super::user_token::UserAuthToken;
+use super::ClientId;
+use crate::requests::*;
+
+#[derive(Debug, Serialize)]
+/// Do not use directly, instead use [`RevokeRequest`]
+pub struct RevokeParams {
+    client_id: Option<ClientId>,
+    token: Option<String>,
+}
+
+impl ParametersExt for RevokeParams {}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Error, Deserialize)]
+/// Errors returned when revoking a token
+pub enum RevokeErrorCodes {
+    #[error("400: Invalid token")]
+    /// The token was already revoked, expired or never valid
+    InvalidToken,
+
+    #[error("400: Malformed Request")]
+    /// The request did not conform to what the endpoint was expecting
+    BadRequestCode,
+
+    #[error("404: Client does not exist")]
+    /// The client id is not the one the token was issued to, or does not exist
+    ClientNotFound,
+
+    #[error("500: Server Error")]
+    /// Twitch may be experiencing internal errors
+    ServerErrorCode,
+}
+
+impl ErrorCodes for RevokeErrorCodes {
+    fn from_status(codes: FailureStatus<u16>) -> Result<FailureStatus<Self>, FailureStatus<u16>> {
+        let status = match codes.status {
+            400 if codes.message.eq_ignore_ascii_case("invalid token") => Self::InvalidToken,
+            400 => Self::BadRequestCode,
+            404 => Self::ClientNotFound,
+            500 => Self::ServerErrorCode,
+            _ => return Err(codes),
+        };
+
+        Ok(FailureStatus {
+            error: codes.error,
+            status,
+            message: codes.message,
+        })
+    }
+}
+
+#[derive(Debug)]
+/// Request to revoke an access token
+pub struct RevokeRequest {
+    params: RevokeParams,
+}
+
+#[cfg_attr(feature = "nightly", doc(spotlight))]
+impl Request for RevokeRequest {
+    const ENDPOINT: &'static str = "https://id.twitch.tv/oauth2/revoke";
+
+    type Headers = ();
+    type Parameters = RevokeParams;
+    type Body = ();
+
+    type Response = ();
+
+    type ErrorCodes = RevokeErrorCodes;
+
+    const METHOD: reqwest::Method = reqwest::Method::POST;
+
+    fn builder() -> Self {
+        Self {
+            params: RevokeParams {
+                client_id: None,
+                token: None,
+            },
+        }
+    }
+
+    fn headers(&self) -> &Self::Headers {
+        &()
+    }
+    fn parameters(&self) -> &Self::Parameters {
+        &self.params
+    }
+    fn body(&self) -> &Self::Body {
+        &()
+    }
+
+    fn ready(&self) -> Result<(), RequestError<Self::ErrorCodes>> {
+        if self.params.client_id.is_none() {
+            Err(RequestError::MalformedRequest(String::from(
+                "field client_id must be set",
+            )))
+        } else if self.params.token.is_none() {
+            Err(RequestError::MalformedRequest(String::from(
+                "field token must be set",
+            )))
+        } else {
+            Ok(())
+        }
+    }
+}
+
+impl RevokeRequest {
+    /// Set the client_id the token was issued to
+    pub fn set_client_id<I: Into<ClientId>>(&mut self, client_id: I) -> &mut Self {
+        self.params.client_id.replace(client_id.into());
+        self
+    }
+
+    /// Set the access token to revoke
+    pub fn set_token<S: Into<String>>(&mut self, token: S) -> &mut Self {
+        self.params.token.replace(token.into());
+        self
+    }
+}
+
+/// Build a complete request from `(client_id, token)`
+impl<I, S> From<(I, S)> for RevokeRequest
+where
+    I: Into<ClientId>,
+    S: Into<String>,
+{
+    fn from((client_id, token): (I, S)) -> Self {
+        Self {
+            params: RevokeParams {
+                client_id: Some(client_id.into()),
+                token: Some(token.into()),
+            },
+        }
+    }
+}
+
+impl From<&UserAuthToken> for RevokeRequest {
+    fn from(token: &UserAuthToken) -> Self {
+        Self::from((token.client_id.clone(), token.token.clone()))
+    }
+}
+
+impl From<&ClientAuthToken> for RevokeRequest {
+    fn from(token: &ClientAuthToken) -> Self {
+        Self::from((token.client_id.clone(), token.token.clone()))
+    }
+}
+
+impl UserAuthToken {
+    /// Revoke this token so it can not be used again
+    pub async fn revoke<C>(&self, client: C) -> Result<(), RequestError<RevokeErrorCodes>>
+    where
+        C: std::borrow::Borrow<reqwest::Client> + Send,
+    {
+        RevokeRequest::from(self).make_request(client).await
+    }
+}
+
+impl ClientAuthToken {
+    /// Revoke this token so it can not be used again
+    pub async fn revoke<C>(&self, client: C) -> Result<(), RequestError<RevokeErrorCodes>>
+    where
+        C: std::borrow::Borrow<reqwest::Client> + Send,
+    {
+        RevokeRequest::from(self).make_request(client).await
+    }
+}
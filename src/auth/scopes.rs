@@ -97,223 +97,679 @@ macro_rules! ident {
 }
 // }}}
 
-/// Represents a single twitch Scope
-#[allow(missing_docs)]
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
-#[repr(usize)]
-pub enum Scope {
-    // General scopes
-    AnalyticsReadExtensions = 0,
-    AnalyticsReadGames,
-
-    BitsRead,
-
-    ChannelEditCommerial,
-    ChannelManageBroadcast,
-    ChannelManageExtensions,
-    ChannelManageRedemptions,
-    ChannelManageVideos,
-
-    ChannelReadEditors,
-    ChannelReadHypeTrain,
-    ChannelReadRedemptions,
-    ChannelReadStreamKey,
-    ChannelReadSubscriptions,
+use serde::de::{self, SeqAccess, Visitor};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::collections::BTreeSet;
+use std::convert::Infallible;
+use std::fmt;
+use std::str::FromStr;
+
+/// Define [`Scope`] with the twitch name of each variant, and the list of all of them
+macro_rules! scopes {
+    ( $( $(#[$meta:meta])* $variant:ident => $spec:literal, )+ ) => {
+        /// Represents a single twitch Scope
+        #[allow(missing_docs)]
+        #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+        pub enum Scope {
+            $( $(#[$meta])* $variant, )+
+        }
 
-    ClipsEdit,
+        impl Scope {
+            /// Every scope known to this crate, in the order they are iterated in a
+            /// [`ScopeSet`]
+            pub const ALL: &'static [Scope] = &[ $( Self::$variant, )+ ];
 
-    ModerationRead,
+            /// Get the Twitch Scope Spec for an internal scope object
+            pub fn as_twitch_str(self) -> &'static str {
+                match self {
+                    $( Self::$variant => $spec, )+
+                }
+            }
 
-    UserEdit,
-    UserEditFollows,
-    UserReadBroadcast,
-    UserReadEmail,
-    UserReadBlockedUsers,
+            /// Turn a Twitch Scope Spec into an internal scope object
+            pub fn from_twitch_str(ts: &str) -> Option<Self> {
+                match ts {
+                    $( $spec => Some(Self::$variant), )+
+                    _ => None,
+                }
+            }
+        }
+    };
+}
 
-    UserManageBlockedUsers,
+scopes! {
+    // General scopes
+    AnalyticsReadExtensions => "analytics:read:extensions",
+    AnalyticsReadGames => "analytics:read:games",
+    BitsRead => "bits:read",
+    ChannelBot => "channel:bot",
+    ChannelManageAds => "channel:manage:ads",
+    ChannelReadAds => "channel:read:ads",
+    ChannelManageBroadcast => "channel:manage:broadcast",
+    ChannelReadCharity => "channel:read:charity",
+    ChannelEditCommerial => "channel:edit:commercial",
+    ChannelReadEditors => "channel:read:editors",
+    ChannelManageExtensions => "channel:manage:extensions",
+    ChannelReadGoals => "channel:read:goals",
+    ChannelReadGuestStar => "channel:read:guest_star",
+    ChannelManageGuestStar => "channel:manage:guest_star",
+    ChannelReadHypeTrain => "channel:read:hype_train",
+    ChannelManageModerators => "channel:manage:moderators",
+    ChannelReadPolls => "channel:read:polls",
+    ChannelManagePolls => "channel:manage:polls",
+    ChannelReadPredictions => "channel:read:predictions",
+    ChannelManagePredictions => "channel:manage:predictions",
+    ChannelManageRaids => "channel:manage:raids",
+    ChannelReadRedemptions => "channel:read:redemptions",
+    ChannelManageRedemptions => "channel:manage:redemptions",
+    ChannelManageSchedule => "channel:manage:schedule",
+    ChannelReadStreamKey => "channel:read:stream_key",
+    ChannelReadSubscriptions => "channel:read:subscriptions",
+    ChannelManageVideos => "channel:manage:videos",
+    ChannelReadVips => "channel:read:vips",
+    ChannelManageVips => "channel:manage:vips",
+    ClipsEdit => "clips:edit",
+    ModerationRead => "moderation:read",
+    ModeratorManageAnnouncements => "moderator:manage:announcements",
+    ModeratorManageAutomod => "moderator:manage:automod",
+    ModeratorReadAutomodSettings => "moderator:read:automod_settings",
+    ModeratorManageAutomodSettings => "moderator:manage:automod_settings",
+    ModeratorReadBannedUsers => "moderator:read:banned_users",
+    ModeratorManageBannedUsers => "moderator:manage:banned_users",
+    ModeratorReadBlockedTerms => "moderator:read:blocked_terms",
+    ModeratorManageBlockedTerms => "moderator:manage:blocked_terms",
+    ModeratorReadChatMessages => "moderator:read:chat_messages",
+    ModeratorManageChatMessages => "moderator:manage:chat_messages",
+    ModeratorReadChatSettings => "moderator:read:chat_settings",
+    ModeratorManageChatSettings => "moderator:manage:chat_settings",
+    ModeratorReadChatters => "moderator:read:chatters",
+    ModeratorReadFollowers => "moderator:read:followers",
+    ModeratorReadGuestStar => "moderator:read:guest_star",
+    ModeratorManageGuestStar => "moderator:manage:guest_star",
+    ModeratorReadModerators => "moderator:read:moderators",
+    ModeratorReadShieldMode => "moderator:read:shield_mode",
+    ModeratorManageShieldMode => "moderator:manage:shield_mode",
+    ModeratorReadShoutouts => "moderator:read:shoutouts",
+    ModeratorManageShoutouts => "moderator:manage:shoutouts",
+    ModeratorReadSuspiciousUsers => "moderator:read:suspicious_users",
+    ModeratorReadUnbanRequests => "moderator:read:unban_requests",
+    ModeratorManageUnbanRequests => "moderator:manage:unban_requests",
+    ModeratorReadVips => "moderator:read:vips",
+    ModeratorReadWarnings => "moderator:read:warnings",
+    ModeratorManageWarnings => "moderator:manage:warnings",
+    UserBot => "user:bot",
+    UserEdit => "user:edit",
+    UserEditBroadcast => "user:edit:broadcast",
+    UserEditFollows => "user:edit:follows",
+    UserReadBlockedUsers => "user:read:blocked_users",
+    UserManageBlockedUsers => "user:manage:blocked_users",
+    UserReadBroadcast => "user:read:broadcast",
+    UserReadChat => "user:read:chat",
+    UserManageChatColor => "user:manage:chat_color",
+    UserReadEmail => "user:read:email",
+    UserReadEmotes => "user:read:emotes",
+    UserReadFollows => "user:read:follows",
+    UserReadModeratedChannels => "user:read:moderated_channels",
+    UserReadSubscriptions => "user:read:subscriptions",
+    UserReadWhispers => "user:read:whispers",
+    UserManageWhispers => "user:manage:whispers",
+    UserWriteChat => "user:write:chat",
 
     // The following scopes are for for chat and PubSub
-    ChannelModerate,
-
-    ChannelEdit,
-    ChatRead,
-
-    WhispersRead,
-    WhispersEdit,
-    // SAFETY: New members must be accounted for in Scope::max(), as that must reflect the total
-    // count of enum variants
+    ChannelModerate => "channel:moderate",
+    ChannelEdit => "chat:edit",
+    ChatRead => "chat:read",
+    WhispersRead => "whispers:read",
+    WhispersEdit => "whispers:edit",
+
+    // OpenID Connect, see crate::auth::oidc
+    OpenId => "openid",
 }
 
-impl Scope {
-    const fn max() -> usize {
-        // SAFETY: Must be updated to reflect the amount of scopes represented by Scope
-        26
-    }
-
-    /// Get the Twitch Scope Spec for an internal scope object
-    pub fn as_twitch_str(self) -> &'static str {
-        match self {
-            Self::AnalyticsReadExtensions => "analytics:read:extensions",
-            Self::AnalyticsReadGames => "analytics:read:games",
-            Self::BitsRead => "bits:read",
-            Self::ChannelEditCommerial => "channel:edit:commercial",
-            Self::ChannelManageBroadcast => "channel:manage:broadcast",
-            Self::ChannelManageExtensions => "channel:manage:extensions",
-            Self::ChannelManageRedemptions => "channel:manage:redemptions",
-            Self::ChannelManageVideos => "channel:manage:videos",
-            Self::ChannelReadEditors => "channel:read:editors",
-            Self::ChannelReadHypeTrain => "channel:read:hype_train",
-            Self::ChannelReadRedemptions => "channel:read:redemptions",
-            Self::ChannelReadStreamKey => "channel:read:stream_key",
-            Self::ChannelReadSubscriptions => "channel:read:subscriptions",
-            Self::ClipsEdit => "clips:edit",
-            Self::ModerationRead => "moderation:read",
-            Self::UserEdit => "user:edit",
-            Self::UserEditFollows => "user:edit:follows",
-            Self::UserReadBroadcast => "user:read:broadcast",
-            Self::UserReadEmail => "user:read:email",
-            Self::UserReadBlockedUsers => "user:read:blocked_users",
-            Self::UserManageBlockedUsers => "user:mange:blocked_users",
-            Self::ChannelModerate => "channel:moderate",
-            Self::ChannelEdit => "chat:edit",
-            Self::ChatRead => "chat:read",
-            Self::WhispersRead => "whispers:read",
-            Self::WhispersEdit => "whispers:edit",
-        }
-    }
+/// Number of words needed to hold a bit for every [`Scope`]
+const WORDS: usize = Scope::ALL.len().div_ceil(64);
 
-    /// Turn a Twitch Scope Spec into an internal scope object
-    pub fn from_twitch_str(ts: &str) -> Option<Self> {
-        match ts {
-            "analytics:read:extensions" => Some(Self::AnalyticsReadExtensions),
-            "analytics:read:games" => Some(Self::AnalyticsReadGames),
-            "bits:read" => Some(Self::BitsRead),
-            "channel:edit:commercial" => Some(Self::ChannelEditCommerial),
-            "channel:manage:broadcast" => Some(Self::ChannelManageBroadcast),
-            "channel:manage:extensions" => Some(Self::ChannelManageExtensions),
-            "channel:manage:redemptions" => Some(Self::ChannelManageRedemptions),
-            "channel:manage:videos" => Some(Self::ChannelManageVideos),
-            "channel:read:editors" => Some(Self::ChannelReadEditors),
-            "channel:read:hype_train" => Some(Self::ChannelReadHypeTrain),
-            "channel:read:redemptions" => Some(Self::ChannelReadRedemptions),
-            "channel:read:stream_key" => Some(Self::ChannelReadStreamKey),
-            "channel:read:subscriptions" => Some(Self::ChannelReadSubscriptions),
-            "clips:edit" => Some(Self::ClipsEdit),
-            "moderation:read" => Some(Self::ModerationRead),
-            "user:edit" => Some(Self::UserEdit),
-            "user:edit:follows" => Some(Self::UserEditFollows),
-            "user:read:broadcast" => Some(Self::UserReadBroadcast),
-            "user:read:email" => Some(Self::UserReadEmail),
-            "user:read:blocked_users" => Some(Self::UserReadBlockedUsers),
-            "user:mange:blocked_users" => Some(Self::UserManageBlockedUsers),
-            "channel:moderate" => Some(Self::ChannelModerate),
-            "chat:edit" => Some(Self::ChannelEdit),
-            "chat:read" => Some(Self::ChatRead),
-            "whispers:read" => Some(Self::WhispersRead),
-            "whispers:edit" => Some(Self::WhispersEdit),
-            _ => None,
-        }
+impl Scope {
+    fn word_and_bit(self) -> (usize, u64) {
+        let index = self as usize;
+        (index / 64, 1 << (index % 64))
     }
 }
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
 /// Represents a set of scopes available with a specific bearer auth key
+///
+/// Scopes that are not known to this crate are kept by their twitch name, so they are
+/// sent back to twitch unchanged.
 pub struct ScopeSet {
-    scopes: usize,
+    scopes: [u64; WORDS],
+    unknown: BTreeSet<String>,
 }
 
 impl ScopeSet {
     /// Create a new empty set of scopes
     pub fn new() -> Self {
-        Self { scopes: 0 }
+        Self::default()
     }
 
     #[allow(missing_docs)]
     pub fn contains(&self, scope: Scope) -> bool {
-        self.scopes & (1 << scope as usize) != 0
+        let (word, bit) = scope.word_and_bit();
+        self.scopes[word] & bit != 0
     }
 
     /// Ass a scope to the set, does nothing if the set already contains the scope
     pub fn insert(&mut self, scope: Scope) {
-        self.scopes |= 1 << scope as usize;
+        let (word, bit) = scope.word_and_bit();
+        self.scopes[word] |= bit;
     }
 
     /// Remove a scope from the set, does nothing if the set does not contain the scope
     pub fn remove(&mut self, scope: Scope) {
-        self.scopes &= !(1 << scope as usize);
+        let (word, bit) = scope.word_and_bit();
+        self.scopes[word] &= !bit;
+    }
+
+    /// If the set contains the scope with the twitch name `spec`, known or not
+    pub fn contains_spec(&self, spec: &str) -> bool {
+        match Scope::from_twitch_str(spec) {
+            Some(scope) => self.contains(scope),
+            None => self.unknown.contains(spec),
+        }
+    }
+
+    /// Add the scope with the twitch name `spec`, keeping it by name if it is not known
+    /// to this crate
+    pub fn insert_spec(&mut self, spec: &str) {
+        match Scope::from_twitch_str(spec) {
+            Some(scope) => self.insert(scope),
+            None => {
+                if !spec.is_empty() {
+                    self.unknown.insert(spec.to_string());
+                }
+            }
+        }
+    }
+
+    /// Remove the scope with the twitch name `spec`, known or not
+    pub fn remove_spec(&mut self, spec: &str) {
+        match Scope::from_twitch_str(spec) {
+            Some(scope) => self.remove(scope),
+            None => {
+                self.unknown.remove(spec);
+            }
+        }
+    }
+
+    /// If the set contains no scopes
+    pub fn is_empty(&self) -> bool {
+        self.scopes.iter().all(|w| *w == 0) && self.unknown.is_empty()
+    }
+
+    /// The number of scopes in the set, known or not
+    pub fn len(&self) -> usize {
+        let known: u32 = self.scopes.iter().map(|w| w.count_ones()).sum();
+        known as usize + self.unknown.len()
     }
 
     /// Get a borrowing iterator over Self of Twitch Scope Specs
-    pub fn spec_iter<'set>(&'set self) -> impl Iterator<Item = &'static str> + 'set {
-        SpecIter(ScopeIter {
-            cursor: 0,
-            set: &self,
-        })
+    ///
+    /// Known scopes come first, followed by the scopes this crate does not know.
+    pub fn spec_iter<'set>(&'set self) -> impl Iterator<Item = &'set str> + 'set {
+        self.scope_iter()
+            .map(|s| -> &'set str { s.as_twitch_str() })
+            .chain(self.unknown_iter())
     }
 
     /// Get a borrowing iterator over Self of Scope Enum variants
     pub fn scope_iter<'set>(&'set self) -> impl Iterator<Item = Scope> + 'set {
-        ScopeIter {
-            cursor: 0,
-            set: &self,
-        }
+        Scope::ALL
+            .iter()
+            .copied()
+            .filter(move |s| self.contains(*s))
+    }
+
+    /// Get a borrowing iterator over the twitch names of scopes not known to this crate
+    pub fn unknown_iter<'set>(&'set self) -> impl Iterator<Item = &'set str> + 'set {
+        self.unknown.iter().map(String::as_str)
     }
 }
 
-struct SpecIter<'set>(ScopeIter<'set>);
+impl<'a> std::iter::FromIterator<&'a str> for ScopeSet {
+    fn from_iter<I>(iter: I) -> Self
+    where
+        I: IntoIterator<Item = &'a str>,
+    {
+        let mut scope_set = ScopeSet::new();
 
-impl<'set> Iterator for SpecIter<'set> {
-    type Item = &'static str;
+        for spec in iter.into_iter() {
+            scope_set.insert_spec(spec);
+        }
 
-    fn next(&mut self) -> Option<Self::Item> {
-        self.0.next().map(Scope::as_twitch_str)
+        scope_set
     }
 }
 
-impl<'a> std::iter::FromIterator<&'a str> for ScopeSet {
+impl std::iter::FromIterator<Scope> for ScopeSet {
     fn from_iter<I>(iter: I) -> Self
     where
-        I: IntoIterator<Item = &'a str>,
+        I: IntoIterator<Item = Scope>,
     {
         let mut scope_set = ScopeSet::new();
+        scope_set.extend(iter);
+        scope_set
+    }
+}
+
+impl Extend<Scope> for ScopeSet {
+    fn extend<I>(&mut self, iter: I)
+    where
+        I: IntoIterator<Item = Scope>,
+    {
+        for scope in iter {
+            self.insert(scope);
+        }
+    }
+}
+
+impl ScopeSet {
+    /// Every scope that is in `self`, `other` or both
+    pub fn union(&self, other: &ScopeSet) -> ScopeSet {
+        let mut set = self.clone();
+        for (word, other) in set.scopes.iter_mut().zip(other.scopes.iter()) {
+            *word |= other;
+        }
+        set.unknown.extend(other.unknown.iter().cloned());
+        set
+    }
+
+    /// Every scope that is in both `self` and `other`
+    pub fn intersection(&self, other: &ScopeSet) -> ScopeSet {
+        let mut set = self.clone();
+        for (word, other) in set.scopes.iter_mut().zip(other.scopes.iter()) {
+            *word &= other;
+        }
+        set.unknown.retain(|s| other.unknown.contains(s));
+        set
+    }
+
+    /// Every scope that is in `self` but not in `other`
+    pub fn difference(&self, other: &ScopeSet) -> ScopeSet {
+        let mut set = self.clone();
+        for (word, other) in set.scopes.iter_mut().zip(other.scopes.iter()) {
+            *word &= !other;
+        }
+        set.unknown.retain(|s| !other.unknown.contains(s));
+        set
+    }
+
+    /// If every scope in `self` is also in `other`
+    pub fn is_subset(&self, other: &ScopeSet) -> bool {
+        self.difference(other).is_empty()
+    }
+
+    /// If every scope in `other` is also in `self`
+    pub fn is_superset(&self, other: &ScopeSet) -> bool {
+        other.is_subset(self)
+    }
+}
+
+impl std::ops::BitOr for &ScopeSet {
+    type Output = ScopeSet;
+
+    fn bitor(self, rhs: &ScopeSet) -> ScopeSet {
+        self.union(rhs)
+    }
+}
 
-        for maybe_scope in iter.into_iter() {
-            if let Some(scope) = Scope::from_twitch_str(maybe_scope) {
-                scope_set.insert(scope);
+impl std::ops::BitAnd for &ScopeSet {
+    type Output = ScopeSet;
+
+    fn bitand(self, rhs: &ScopeSet) -> ScopeSet {
+        self.intersection(rhs)
+    }
+}
+
+impl std::ops::Sub for &ScopeSet {
+    type Output = ScopeSet;
+
+    fn sub(self, rhs: &ScopeSet) -> ScopeSet {
+        self.difference(rhs)
+    }
+}
+
+/// Formats the set as the space separated `scope` parameter twitch expects
+impl fmt::Display for ScopeSet {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, spec) in self.spec_iter().enumerate() {
+            if i > 0 {
+                f.write_str(" ")?;
             }
+            f.write_str(spec)?;
         }
+        Ok(())
+    }
+}
 
-        scope_set
+/// Parses the space separated `scope` parameter, keeping scopes that are not known
+impl FromStr for ScopeSet {
+    type Err = Infallible;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(s.split_whitespace().collect())
+    }
+}
+
+/// Serialized as a list of twitch scope names, see [`space_delimited`] for the string form
+impl Serialize for ScopeSet {
+    fn serialize<S>(&self, ser: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        ser.collect_seq(self.spec_iter())
+    }
+}
+
+/// Deserialized from either a list of twitch scope names or a space separated string,
+/// `null` is an empty set
+///
+/// The form is told apart with `deserialize_any`, so only self describing formats such as
+/// json can be read. For other formats use [`space_delimited`] on the field.
+impl<'de> Deserialize<'de> for ScopeSet {
+    fn deserialize<D>(de: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        de.deserialize_any(ScopeSetVisitor)
+    }
+}
+
+struct ScopeSetVisitor;
+
+impl<'de> Visitor<'de> for ScopeSetVisitor {
+    type Value = ScopeSet;
+
+    fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("a list of scopes or a space separated string of scopes")
+    }
+
+    fn visit_str<E: de::Error>(self, v: &str) -> Result<ScopeSet, E> {
+        Ok(v.split_whitespace().collect())
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<ScopeSet, A::Error> {
+        let mut set = ScopeSet::new();
+        while let Some(spec) = seq.next_element::<std::borrow::Cow<'de, str>>()? {
+            set.insert_spec(&spec);
+        }
+        Ok(set)
+    }
+
+    fn visit_unit<E: de::Error>(self) -> Result<ScopeSet, E> {
+        Ok(ScopeSet::new())
+    }
+
+    fn visit_none<E: de::Error>(self) -> Result<ScopeSet, E> {
+        Ok(ScopeSet::new())
+    }
+}
+
+/// Serialize a [`ScopeSet`] as a space separated string, for use with
+/// `#[serde(with = "twitch_api_rs::auth::scopes::space_delimited")]`
+///
+/// Either form is accepted when deserializing.
+pub mod space_delimited {
+    use super::ScopeSet;
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    #[allow(missing_docs)]
+    pub fn serialize<S>(scopes: &ScopeSet, ser: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        ser.collect_str(scopes)
+    }
+
+    #[allow(missing_docs)]
+    pub fn deserialize<'de, D>(de: D) -> Result<ScopeSet, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        ScopeSet::deserialize(de)
     }
 }
 
-struct ScopeIter<'set> {
-    cursor: usize,
-    set: &'set ScopeSet,
+// {{{ requirements
+/// Scopes an endpoint needs, as a combination of single scopes
+///
+/// Build one with [`ScopeRequirement::all_of`] and [`ScopeRequirement::any_of`], or from
+/// a single [`Scope`]. Combine the requirements of every endpoint an application calls to
+/// find the scopes to request, see [`ScopeRequirement::authorize_scopes`].
+///
+/// ```
+/// # use twitch_api_rs::auth::scopes::{Scope, ScopeRequirement, ScopeSet};
+/// let vips = ScopeRequirement::any_of([Scope::ChannelReadVips, Scope::ChannelManageVips]);
+/// let needed = ScopeRequirement::all_of([vips, Scope::ClipsEdit.into()]);
+///
+/// let granted: ScopeSet = "clips:edit".parse().unwrap();
+/// let missing = needed.check(&granted).unwrap_err();
+/// assert_eq!(
+///     missing.to_string(),
+///     "Missing scopes: channel:read:vips or channel:manage:vips"
+/// );
+///
+/// assert_eq!(
+///     needed.authorize_scopes().to_string(),
+///     "channel:read:vips clips:edit"
+/// );
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum ScopeRequirement {
+    /// This one scope
+    Scope(Scope),
+    /// Every one of these requirements, none at all if empty
+    AllOf(Vec<ScopeRequirement>),
+    /// At least one of these requirements, can never be met if empty
+    AnyOf(Vec<ScopeRequirement>),
 }
 
-impl<'set> Iterator for ScopeIter<'set> {
-    type Item = Scope;
-
-    fn next(&mut self) -> Option<Self::Item> {
-        while self.cursor < Scope::max() {
-            // SAFETY:
-            // This is safe because we know that self.cursor will never be >= Scope::max()
-            // which represents the largest usize that is a valid usize pattern that
-            // can be transumeted into a Scope variant
-            let current_scope: Scope = unsafe { std::mem::transmute(self.cursor) };
-
-            if self.set.contains(current_scope) {
-                self.cursor += 1;
-                return Some(current_scope);
-            } else {
-                self.cursor += 1;
+impl ScopeRequirement {
+    /// No scopes are needed
+    pub fn none() -> Self {
+        Self::AllOf(Vec::new())
+    }
+
+    /// Every one of `requirements` is needed
+    pub fn all_of<I, R>(requirements: I) -> Self
+    where
+        I: IntoIterator<Item = R>,
+        R: Into<ScopeRequirement>,
+    {
+        Self::AllOf(requirements.into_iter().map(Into::into).collect())
+    }
+
+    /// At least one of `requirements` is needed
+    pub fn any_of<I, R>(requirements: I) -> Self
+    where
+        I: IntoIterator<Item = R>,
+        R: Into<ScopeRequirement>,
+    {
+        Self::AnyOf(requirements.into_iter().map(Into::into).collect())
+    }
+
+    /// If `scopes` meets the requirement
+    pub fn is_satisfied_by(&self, scopes: &ScopeSet) -> bool {
+        match self {
+            Self::Scope(scope) => scopes.contains(*scope),
+            Self::AllOf(all) => all.iter().all(|r| r.is_satisfied_by(scopes)),
+            Self::AnyOf(any) => any.iter().any(|r| r.is_satisfied_by(scopes)),
+        }
+    }
+
+    /// The part of the requirement that `scopes` does not meet, `None` if it meets all
+    /// of it
+    ///
+    /// Scopes that were granted are left out, so an [`ScopeRequirement::AllOf`] only
+    /// lists the scopes that are still missing.
+    pub fn missing(&self, scopes: &ScopeSet) -> Option<ScopeRequirement> {
+        if self.is_satisfied_by(scopes) {
+            return None;
+        }
+
+        Some(match self {
+            Self::Scope(scope) => Self::Scope(*scope),
+            Self::AllOf(all) => {
+                Self::AllOf(all.iter().filter_map(|r| r.missing(scopes)).collect()).simplify()
+            }
+            Self::AnyOf(any) => {
+                Self::AnyOf(any.iter().filter_map(|r| r.missing(scopes)).collect()).simplify()
+            }
+        })
+    }
+
+    /// Check that `scopes` meets the requirement
+    pub fn check(&self, scopes: &ScopeSet) -> Result<(), MissingScopes> {
+        match self.missing(scopes) {
+            None => Ok(()),
+            Some(missing) => Err(MissingScopes {
+                add: missing.scopes_to_add(scopes),
+                missing,
+            }),
+        }
+    }
+
+    /// The smallest set of scopes found to add to `granted` so the requirement is met
+    ///
+    /// Alternatives that are already granted, or need fewer new scopes, are preferred.
+    pub fn scopes_to_add(&self, granted: &ScopeSet) -> ScopeSet {
+        let mut chosen = granted.clone();
+        self.choose(&mut chosen);
+
+        // Drop scopes that an alternative chosen later made unnecessary
+        let added = chosen.difference(granted);
+        for scope in added.scope_iter() {
+            chosen.remove(scope);
+            if !self.is_satisfied_by(&chosen) {
+                chosen.insert(scope);
+            }
+        }
+
+        chosen.difference(granted)
+    }
+
+    /// The scopes to request in the authorize url so that the requirement is met
+    pub fn authorize_scopes(&self) -> ScopeSet {
+        self.scopes_to_add(&ScopeSet::new())
+    }
+
+    /// Add scopes to `chosen` until the requirement is met
+    fn choose(&self, chosen: &mut ScopeSet) {
+        match self {
+            Self::Scope(scope) => chosen.insert(*scope),
+            Self::AllOf(all) => {
+                // Choose what is certainly needed first, so alternatives can reuse it
+                let (any, certain): (Vec<_>, Vec<_>) =
+                    all.iter().partition(|r| matches!(r, Self::AnyOf(_)));
+                for requirement in certain.into_iter().chain(any) {
+                    requirement.choose(chosen);
+                }
+            }
+            Self::AnyOf(any) => {
+                if self.is_satisfied_by(chosen) {
+                    return;
+                }
+
+                let best = any
+                    .iter()
+                    .map(|r| {
+                        let mut with = chosen.clone();
+                        r.choose(&mut with);
+                        with
+                    })
+                    .min_by_key(ScopeSet::len);
+
+                if let Some(best) = best {
+                    *chosen = best;
+                }
+            }
+        }
+    }
+
+    /// Collapse combinators with a single requirement
+    fn simplify(self) -> Self {
+        match self {
+            Self::AllOf(mut v) | Self::AnyOf(mut v) if v.len() == 1 => v.pop().unwrap(),
+            other => other,
+        }
+    }
+
+    fn fmt_nested(&self, f: &mut fmt::Formatter<'_>, nested: bool) -> fmt::Result {
+        let (list, sep) = match self {
+            Self::Scope(scope) => return f.write_str(scope.as_twitch_str()),
+            Self::AllOf(all) if all.is_empty() => return f.write_str("no scopes"),
+            Self::AnyOf(any) if any.is_empty() => return f.write_str("an impossible scope"),
+            Self::AllOf(all) => (all, " and "),
+            Self::AnyOf(any) => (any, " or "),
+        };
+
+        let group = nested && list.len() > 1;
+        if group {
+            f.write_str("(")?;
+        }
+        for (i, requirement) in list.iter().enumerate() {
+            if i > 0 {
+                f.write_str(sep)?;
             }
+            requirement.fmt_nested(f, true)?;
         }
-        None
+        if group {
+            f.write_str(")")?;
+        }
+        Ok(())
+    }
+}
+
+impl From<Scope> for ScopeRequirement {
+    fn from(scope: Scope) -> Self {
+        Self::Scope(scope)
+    }
+}
+
+/// Written as twitch scope names joined by `and` and `or`, for example
+/// `clips:edit and (chat:read or chat:edit)`
+impl fmt::Display for ScopeRequirement {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.fmt_nested(f, false)
+    }
+}
+
+/// Returned when a [`ScopeSet`] does not meet a [`ScopeRequirement`]
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+#[error("Missing scopes: {missing}")]
+pub struct MissingScopes {
+    missing: ScopeRequirement,
+    add: ScopeSet,
+}
+
+impl MissingScopes {
+    /// The part of the requirement that was not met
+    pub fn requirement(&self) -> &ScopeRequirement {
+        &self.missing
+    }
+
+    /// The fewest scopes found that would meet the requirement if they were granted
+    pub fn scopes_to_add(&self) -> &ScopeSet {
+        &self.add
     }
 }
 
+impl<C: crate::requests::ErrorCodes> From<MissingScopes> for crate::requests::RequestError<C> {
+    fn from(e: MissingScopes) -> Self {
+        Self::ScopesError(e.add.spec_iter().map(String::from).collect())
+    }
+}
+// }}}
+
 #[cfg(test)]
 mod tests {
 
@@ -364,7 +820,7 @@ mod tests {
         scopes.insert(Scope::UserEdit);
         scopes.insert(Scope::ChannelReadEditors);
 
-        let list: Vec<&'static str> = scopes.spec_iter().collect();
+        let list: Vec<&str> = scopes.spec_iter().collect();
 
         assert!(list.contains(&"user:edit"), "Did not set user:edits");
         assert!(
@@ -391,4 +847,160 @@ mod tests {
             "Removed too many scopes"
         );
     }
+
+    #[test]
+    fn every_scope_round_trips() {
+        assert!(
+            Scope::ALL.len() > 64,
+            "Scopes no longer need more than one word"
+        );
+
+        let all: ScopeSet = Scope::ALL.iter().copied().collect();
+        assert_eq!(all.len(), Scope::ALL.len());
+
+        for scope in Scope::ALL {
+            assert_eq!(
+                Scope::from_twitch_str(scope.as_twitch_str()),
+                Some(*scope),
+                "{:?} does not round trip",
+                scope
+            );
+            assert!(all.contains(*scope), "{:?} was not inserted", scope);
+        }
+
+        assert_eq!(all.scope_iter().collect::<Vec<_>>(), Scope::ALL);
+    }
+
+    #[test]
+    fn unknown_scopes_are_kept() {
+        let scopes: ScopeSet = vec!["user:write:chat", "channel:manage:something_new", ""]
+            .into_iter()
+            .collect();
+
+        assert!(scopes.contains(Scope::UserWriteChat));
+        assert!(scopes.contains_spec("channel:manage:something_new"));
+        assert_eq!(scopes.len(), 2);
+        assert_eq!(
+            scopes.spec_iter().collect::<Vec<_>>(),
+            vec!["user:write:chat", "channel:manage:something_new"]
+        );
+
+        let mut scopes = scopes;
+        scopes.remove_spec("channel:manage:something_new");
+        assert_eq!(scopes.unknown_iter().count(), 0);
+        assert!(!scopes.is_empty());
+    }
+
+    #[test]
+    fn set_algebra() {
+        let a: ScopeSet = "chat:read chat:edit custom:scope".parse().unwrap();
+        let b: ScopeSet = "chat:read whispers:read".parse().unwrap();
+
+        assert_eq!(
+            (&a | &b).to_string(),
+            "chat:edit chat:read whispers:read custom:scope"
+        );
+        assert_eq!((&a & &b).to_string(), "chat:read");
+        assert_eq!((&a - &b).to_string(), "chat:edit custom:scope");
+
+        assert!((&a & &b).is_subset(&a));
+        assert!((&a | &b).is_superset(&b));
+        assert!(!a.is_subset(&b));
+        assert!(ScopeSet::new().is_subset(&b));
+    }
+
+    #[test]
+    fn parse_and_display() {
+        let scopes: ScopeSet = "  user:read:email   clips:edit not:known ".parse().unwrap();
+
+        assert!(scopes.contains(Scope::UserReadEmail));
+        assert!(scopes.contains(Scope::ClipsEdit));
+        assert_eq!(scopes.to_string(), "clips:edit user:read:email not:known");
+        assert_eq!(scopes.to_string().parse::<ScopeSet>().unwrap(), scopes);
+        assert_eq!(ScopeSet::new().to_string(), "");
+    }
+
+    #[test]
+    fn serde_forms() {
+        let scopes: ScopeSet = "clips:edit not:known".parse().unwrap();
+
+        assert_eq!(
+            serde_json::to_string(&scopes).unwrap(),
+            r#"["clips:edit","not:known"]"#
+        );
+        assert_eq!(
+            serde_json::from_str::<ScopeSet>(r#"["clips:edit","not:known"]"#).unwrap(),
+            scopes
+        );
+        assert_eq!(
+            serde_json::from_str::<ScopeSet>(r#""clips:edit not:known""#).unwrap(),
+            scopes
+        );
+        assert!(serde_json::from_str::<ScopeSet>("null").unwrap().is_empty());
+        assert!(serde_json::from_str::<ScopeSet>("5").is_err());
+
+        #[derive(Debug, PartialEq, Serialize, Deserialize)]
+        struct Token {
+            #[serde(with = "space_delimited")]
+            scope: ScopeSet,
+        }
+
+        let token = Token { scope: scopes };
+        let json = serde_json::to_string(&token).unwrap();
+        assert_eq!(json, r#"{"scope":"clips:edit not:known"}"#);
+        assert_eq!(serde_json::from_str::<Token>(&json).unwrap(), token);
+    }
+
+    #[test]
+    fn requirement_reports_missing() {
+        let needed = ScopeRequirement::all_of([
+            ScopeRequirement::from(Scope::ClipsEdit),
+            Scope::UserReadEmail.into(),
+            ScopeRequirement::any_of([Scope::ChatRead, Scope::ChannelEdit]),
+        ]);
+        assert_eq!(
+            needed.to_string(),
+            "clips:edit and user:read:email and (chat:read or chat:edit)"
+        );
+
+        let granted: ScopeSet = "clips:edit chat:edit".parse().unwrap();
+        let missing = needed.check(&granted).unwrap_err();
+        assert_eq!(
+            missing.requirement(),
+            &ScopeRequirement::Scope(Scope::UserReadEmail)
+        );
+        assert_eq!(missing.scopes_to_add().to_string(), "user:read:email");
+
+        let granted: ScopeSet = "clips:edit chat:read user:read:email".parse().unwrap();
+        assert!(needed.check(&granted).is_ok());
+        assert_eq!(needed.missing(&granted), None);
+
+        assert!(ScopeRequirement::none().is_satisfied_by(&ScopeSet::new()));
+        assert!(!ScopeRequirement::any_of(Vec::<Scope>::new()).is_satisfied_by(&granted));
+    }
+
+    #[test]
+    fn authorize_scopes_reuse_alternatives() {
+        let endpoints = ScopeRequirement::all_of([
+            ScopeRequirement::any_of([
+                ScopeRequirement::all_of([Scope::ModeratorReadChatters, Scope::ChatRead]),
+                Scope::ModerationRead.into(),
+            ]),
+            ScopeRequirement::any_of([Scope::ChannelReadVips, Scope::ChannelManageVips]),
+            ScopeRequirement::any_of([Scope::ChannelManageVips, Scope::ModeratorReadVips]),
+            Scope::ModerationRead.into(),
+        ]);
+
+        assert_eq!(
+            endpoints.authorize_scopes().to_string(),
+            "channel:manage:vips moderation:read"
+        );
+        assert!(endpoints.is_satisfied_by(&endpoints.authorize_scopes()));
+
+        let granted: ScopeSet = "channel:read:vips".parse().unwrap();
+        assert_eq!(
+            endpoints.scopes_to_add(&granted).to_string(),
+            "channel:manage:vips moderation:read"
+        );
+    }
 }
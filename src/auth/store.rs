@@ -0,0 +1,354 @@
+//! Persistence for tokens, so they survive restarts
+//!
+//! A [`TokenStore`] set with [`crate::client::ClientBuilder::token_store`] is used by the
+//! client to load tokens instead of requesting new ones, and to save tokens every time
+//! they are refreshed.
+
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::fmt::{self, Debug, Display};
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use thiserror::Error;
+
+use super::client_credentials::ClientAuthToken;
+use super::scopes::ScopeSet;
+use super::user_token::UserAuthToken;
+use super::{AuthToken, ClientId};
+use crate::values::users::{UserId, UserLogin};
+
+/// What a stored token belongs to
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum TokenKey {
+    /// The app access token of a client
+    App(ClientId),
+    /// The user access token of a user
+    User(UserId),
+}
+
+impl Display for TokenKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::App(id) => write!(f, "app:{}", **id),
+            Self::User(id) => write!(f, "user:{}", **id),
+        }
+    }
+}
+
+/// A token as it is saved by a [`TokenStore`]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StoredToken {
+    /// The access token sent with requests
+    pub access_token: String,
+
+    /// Token used to get a new access token, only for user tokens
+    #[serde(default)]
+    pub refresh_token: Option<String>,
+
+    /// The client the token was issued to
+    pub client_id: ClientId,
+
+    /// The scopes the token was granted, as twitch names them
+    #[serde(default)]
+    pub scopes: Vec<String>,
+
+    /// The user the token acts for
+    #[serde(default)]
+    pub user_id: Option<UserId>,
+
+    /// The login name of the user the token acts for
+    #[serde(default)]
+    pub login: Option<UserLogin>,
+
+    /// When the token was issued, in seconds since the unix epoch, only for app tokens
+    #[serde(default)]
+    pub issued_at: Option<u64>,
+
+    /// When the token expires, in seconds since the unix epoch
+    #[serde(default)]
+    pub expires_at: Option<u64>,
+}
+
+impl StoredToken {
+    /// Store a user token that expires after `expires_in`, if known
+    pub fn from_user(token: &UserAuthToken, expires_in: Option<Duration>) -> Self {
+        Self {
+            access_token: token.token.clone(),
+            refresh_token: token.refresh_token.clone(),
+            client_id: token.client_id.clone(),
+            scopes: token.scopes().spec_iter().map(String::from).collect(),
+            user_id: token.user_id.clone(),
+            login: token.login.clone(),
+            issued_at: None,
+            expires_at: expires_in.map(expires_at),
+        }
+    }
+
+    /// Store an app token that expires after `expires_in`, if known
+    pub fn from_app(token: &ClientAuthToken, expires_in: Option<Duration>) -> Self {
+        Self {
+            access_token: token.token.clone(),
+            refresh_token: None,
+            client_id: token.client_id.clone(),
+            scopes: Vec::new(),
+            user_id: None,
+            login: None,
+            issued_at: Some(unix_secs(SystemTime::now() - token.issued_at().elapsed())),
+            expires_at: expires_in.map(expires_at),
+        }
+    }
+
+    /// The user token that was stored
+    pub fn to_user(&self) -> UserAuthToken {
+        let scopes: ScopeSet = self.scopes.iter().map(String::as_str).collect();
+        let mut token = UserAuthToken::new(
+            self.access_token.clone(),
+            self.refresh_token.clone(),
+            self.client_id.clone(),
+            scopes,
+        );
+        token.user_id = self.user_id.clone();
+        token.login = self.login.clone();
+        token
+    }
+
+    /// The app token that was stored
+    ///
+    /// Tokens stored without an issue time are treated as issued when they are restored.
+    pub fn to_app(&self) -> ClientAuthToken {
+        let mut token = ClientAuthToken::new(self.access_token.clone(), self.client_id.clone());
+
+        let issued = self.issued_at.and_then(|secs| {
+            let age = SystemTime::now()
+                .duration_since(UNIX_EPOCH + Duration::from_secs(secs))
+                .unwrap_or_default();
+            Instant::now().checked_sub(age).map(|at| (secs, at))
+        });
+
+        let lifetime = match issued {
+            Some((secs, at)) => {
+                token = token.issued(at);
+                self.expires_at
+                    .map(|expires| Duration::from_secs(expires.saturating_sub(secs)))
+            }
+            None => self.expires_in(),
+        };
+
+        match lifetime {
+            Some(lifetime) => token.with_expiry(lifetime),
+            None => token,
+        }
+    }
+
+    /// How long until the token expires, zero if it already has, `None` if not known
+    pub fn expires_in(&self) -> Option<Duration> {
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default();
+        self.expires_at
+            .map(|at| Duration::from_secs(at).saturating_sub(now))
+    }
+}
+
+fn expires_at(expires_in: Duration) -> u64 {
+    unix_secs(SystemTime::now() + expires_in)
+}
+
+fn unix_secs(time: SystemTime) -> u64 {
+    time.duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs()
+}
+
+/// Returned when a [`TokenStore`] could not load or save a token
+#[derive(Debug, Error)]
+pub enum StoreError {
+    #[error("{from}")]
+    /// The store could not be read or written
+    Io {
+        #[from]
+        #[allow(missing_docs)]
+        from: std::io::Error,
+    },
+
+    #[error("{from}")]
+    /// The stored tokens could not be read or written as json
+    Json {
+        #[from]
+        #[allow(missing_docs)]
+        from: serde_json::Error,
+    },
+
+    #[error("{0}")]
+    /// Any other error from a custom store
+    Other(Box<dyn std::error::Error + Send + Sync>),
+}
+
+/// Somewhere tokens can be saved and loaded from
+///
+/// Implement this to keep tokens in a database or secret manager, or use one of
+/// [`JsonFileStore`] and [`MemoryStore`].
+///
+/// The store is called synchronously, from inside the async methods of the client as
+/// well as its plain ones, so every call blocks the thread it runs on until it returns.
+/// Stores that talk to a slow backend should answer from a cache and write in the
+/// background.
+pub trait TokenStore: Debug + Send + Sync {
+    /// Get the token saved under `key`, if any
+    fn load(&self, key: &TokenKey) -> Result<Option<StoredToken>, StoreError>;
+
+    /// Save `token` under `key`, replacing any token that was there
+    fn save(&self, key: &TokenKey, token: &StoredToken) -> Result<(), StoreError>;
+
+    /// Remove the token saved under `key`, if any
+    fn delete(&self, key: &TokenKey) -> Result<(), StoreError>;
+}
+
+impl<S: TokenStore + ?Sized> TokenStore for std::sync::Arc<S> {
+    fn load(&self, key: &TokenKey) -> Result<Option<StoredToken>, StoreError> {
+        (**self).load(key)
+    }
+
+    fn save(&self, key: &TokenKey, token: &StoredToken) -> Result<(), StoreError> {
+        (**self).save(key, token)
+    }
+
+    fn delete(&self, key: &TokenKey) -> Result<(), StoreError> {
+        (**self).delete(key)
+    }
+}
+
+/// Keeps tokens in memory, useful for tests or to share tokens between clients
+#[derive(Debug, Default)]
+pub struct MemoryStore {
+    tokens: Mutex<HashMap<TokenKey, StoredToken>>,
+}
+
+impl MemoryStore {
+    /// Create an empty store
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, HashMap<TokenKey, StoredToken>> {
+        self.tokens.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+impl TokenStore for MemoryStore {
+    fn load(&self, key: &TokenKey) -> Result<Option<StoredToken>, StoreError> {
+        Ok(self.lock().get(key).cloned())
+    }
+
+    fn save(&self, key: &TokenKey, token: &StoredToken) -> Result<(), StoreError> {
+        self.lock().insert(key.clone(), token.clone());
+        Ok(())
+    }
+
+    fn delete(&self, key: &TokenKey) -> Result<(), StoreError> {
+        self.lock().remove(key);
+        Ok(())
+    }
+}
+
+/// Keeps all tokens in a single json file
+///
+/// Every save replaces the file with a new one, written next to it first so a crash can
+/// not leave it half written. On unix the file may only be read by its owner.
+///
+/// Files are read and written with blocking io, which is fine for the few small writes
+/// a client makes but ties up the runtime thread while it happens.
+#[derive(Debug)]
+pub struct JsonFileStore {
+    path: PathBuf,
+    lock: Mutex<()>,
+}
+
+impl JsonFileStore {
+    /// Use the file at `path`, which is created when the first token is saved
+    pub fn new<P: Into<PathBuf>>(path: P) -> Self {
+        Self {
+            path: path.into(),
+            lock: Mutex::new(()),
+        }
+    }
+
+    /// The file the tokens are kept in
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    fn read(&self) -> Result<BTreeMap<String, StoredToken>, StoreError> {
+        match fs::read(&self.path) {
+            Ok(bytes) => Ok(serde_json::from_slice(&bytes)?),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(BTreeMap::new()),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    fn write(&self, tokens: &BTreeMap<String, StoredToken>) -> Result<(), StoreError> {
+        let bytes = serde_json::to_vec_pretty(tokens)?;
+
+        let mut tmp_name = self.path.file_name().unwrap_or_default().to_os_string();
+        tmp_name.push(format!(".{}.tmp", std::process::id()));
+        let tmp = self.path.with_file_name(tmp_name);
+
+        // Left behind by a crash, and may have been created with other permissions
+        match fs::remove_file(&tmp) {
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound => return Err(e.into()),
+            _ => {}
+        }
+
+        let mut options = fs::OpenOptions::new();
+        options.write(true).create_new(true);
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::OpenOptionsExt;
+            options.mode(0o600);
+        }
+
+        let result = options.open(&tmp).and_then(|mut file| {
+            file.write_all(&bytes)?;
+            file.sync_all()
+        });
+
+        if let Err(e) = result.and_then(|_| fs::rename(&tmp, &self.path)) {
+            let _ = fs::remove_file(&tmp);
+            return Err(e.into());
+        }
+
+        Ok(())
+    }
+
+    fn update<F>(&self, f: F) -> Result<(), StoreError>
+    where
+        F: FnOnce(&mut BTreeMap<String, StoredToken>),
+    {
+        let _guard = self.lock.lock().unwrap_or_else(|e| e.into_inner());
+        let mut tokens = self.read()?;
+        f(&mut tokens);
+        self.write(&tokens)
+    }
+}
+
+impl TokenStore for JsonFileStore {
+    fn load(&self, key: &TokenKey) -> Result<Option<StoredToken>, StoreError> {
+        let _guard = self.lock.lock().unwrap_or_else(|e| e.into_inner());
+        Ok(self.read()?.remove(&key.to_string()))
+    }
+
+    fn save(&self, key: &TokenKey, token: &StoredToken) -> Result<(), StoreError> {
+        self.update(|tokens| {
+            tokens.insert(key.to_string(), token.clone());
+        })
+    }
+
+    fn delete(&self, key: &TokenKey) -> Result<(), StoreError> {
+        self.update(|tokens| {
+            tokens.remove(&key.to_string());
+        })
+    }
+}
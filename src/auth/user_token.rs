@@ -0,0 +1,81 @@
+//! [`User access tokens`], which act on behalf of a single twitch user
+//!
+//! [`User access tokens`]: https://dev.twitch.tv/docs/authentication#user-access-tokens
+
+use reqwest::RequestBuilder;
+
+use super::scopes::ScopeSet;
+use super::ClientId;
+use crate::requests::Headers;
+use crate::values::users::{UserId, UserLogin};
+
+/// Represents a user access token, and the refresh token that can be used to renew it
+#[derive(Debug, Clone)]
+pub struct UserAuthToken {
+    scopes: ScopeSet,
+
+    /// The access token sent with requests
+    pub token: String,
+
+    /// Token used to get a new access token once this one expires, not given by the
+    /// implicit flow
+    pub refresh_token: Option<String>,
+
+    /// The client the token was issued to
+    pub client_id: ClientId,
+
+    /// The user the token acts for, if known
+    pub user_id: Option<UserId>,
+
+    /// The login name of the user the token acts for, if known
+    pub login: Option<UserLogin>,
+}
+
+impl UserAuthToken {
+    /// Create a new UserAuthToken for an unknown user
+    pub fn new<C>(
+        token: String,
+        refresh_token: Option<String>,
+        client_id: C,
+        scopes: ScopeSet,
+    ) -> Self
+    where
+        C: Into<ClientId>,
+    {
+        Self {
+            scopes,
+            token,
+            refresh_token,
+            client_id: client_id.into(),
+            user_id: None,
+            login: None,
+        }
+    }
+
+    /// Set the user that this token acts for
+    pub fn with_user<I, L>(mut self, user_id: I, login: L) -> Self
+    where
+        I: Into<UserId>,
+        L: Into<UserLogin>,
+    {
+        self.user_id = Some(user_id.into());
+        self.login = Some(login.into());
+        self
+    }
+}
+
+impl Headers for UserAuthToken {
+    fn write_headers(&self, req: RequestBuilder) -> RequestBuilder {
+        req.header("Authorization", format!("Bearer {}", self.token))
+            .header("Client-Id", std::ops::Deref::deref(&self.client_id))
+    }
+}
+
+impl super::AuthToken for UserAuthToken {
+    fn scopes(&self) -> &ScopeSet {
+        &self.scopes
+    }
+}
+
+impl super::UserAccessToken for UserAuthToken {}
+impl super::AppOrUserToken for UserAuthToken {}
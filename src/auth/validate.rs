@@ -0,0 +1,183 @@
+//! [`Validating`] access tokens
+//!
+//! Twitch requires applications that keep a user logged in to validate their token when
+//! they start and every hour after, see
+//! [`Client::spawn_validation`](crate::client::Client::spawn_validation). Validation also
+//! tells which client, user and scopes a token belongs to, so tokens built from a
+//! [`ValidateResponse`] have accurate scopes.
+//!
+//! ```no_run
+//! # use twitch_api_rs::auth::validate::*;
+//! # use twitch_api_rs::requests::Request;
+//! # #[tokio::main]
+//! # async fn main() {
+//! # let access_token = String::new();
+//! let validated = ValidateRequest::from(access_token.clone())
+//!     .make_request(&reqwest::Client::new())
+//!     .await
+//!     .expect("Token is not valid");
+//!
+//! let token = validated.user_token(access_token, None);
+//! # }
+//! ```
+//!
+//! [`Validating`]: https://dev.twitch.tv/docs/authentication/validate-tokens
+
+use reqwest::RequestBuilder;
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+use super::client_credentials::ClientAuthToken;
+use super::scopes::ScopeSet;
+use super::user_token::UserAuthToken;
+use super::ClientId;
+use crate::requests::*;
+use crate::values::users::{UserId, UserLogin};
+
+/// How often twitch asks for tokens to be validated
+pub const VALIDATION_INTERVAL: Duration = Duration::from_secs(60 * 60);
+
+#[derive(Debug)]
+/// Do not use directly, instead use [`ValidateRequest`]
+pub struct ValidateHeaders {
+    token: Option<String>,
+}
+
+impl Headers for ValidateHeaders {
+    fn write_headers(&self, req: RequestBuilder) -> RequestBuilder {
+        match &self.token {
+            Some(token) => req.header("Authorization", format!("OAuth {}", token)),
+            None => req,
+        }
+    }
+}
+
+#[derive(Debug)]
+/// Request to check that an access token is still valid, and get what it is for
+pub struct ValidateRequest {
+    headers: ValidateHeaders,
+}
+
+#[cfg_attr(feature = "nightly", doc(spotlight))]
+impl Request for ValidateRequest {
+    const ENDPOINT: &'static str = "https://id.twitch.tv/oauth2/validate";
+
+    type Headers = ValidateHeaders;
+    type Parameters = ();
+    type Body = ();
+
+    type Response = ValidateResponse;
+
+    type ErrorCodes = CommonResponseCodes;
+
+    const METHOD: reqwest::Method = reqwest::Method::GET;
+
+    fn builder() -> Self {
+        Self {
+            headers: ValidateHeaders { token: None },
+        }
+    }
+
+    fn headers(&self) -> &Self::Headers {
+        &self.headers
+    }
+    fn parameters(&self) -> &Self::Parameters {
+        &()
+    }
+    fn body(&self) -> &Self::Body {
+        &()
+    }
+
+    fn ready(&self) -> Result<(), RequestError<Self::ErrorCodes>> {
+        if self.headers.token.is_none() {
+            Err(RequestError::MalformedRequest(String::from(
+                "field token must be set",
+            )))
+        } else {
+            Ok(())
+        }
+    }
+}
+
+impl ValidateRequest {
+    /// Set the access token to validate
+    pub fn set_token<S: Into<String>>(&mut self, token: S) -> &mut Self {
+        self.headers.token.replace(token.into());
+        self
+    }
+}
+
+/// Build a complete request from the access token to validate
+impl<S: Into<String>> From<S> for ValidateRequest {
+    fn from(token: S) -> Self {
+        Self {
+            headers: ValidateHeaders {
+                token: Some(token.into()),
+            },
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+/// Response from a successful [`ValidateRequest`]
+pub struct ValidateResponse {
+    /// The client the token was issued to
+    pub client_id: ClientId,
+
+    /// The login name of the user, not set for app access tokens
+    #[serde(default)]
+    pub login: Option<UserLogin>,
+
+    /// The id of the user, not set for app access tokens
+    #[serde(default)]
+    pub user_id: Option<UserId>,
+
+    /// The scopes of the token, as twitch names them
+    #[serde(default)]
+    pub scopes: Option<Vec<String>>,
+
+    /// The amount of seconds until the token expires, `0` if it does not expire
+    pub expires_in: u64,
+}
+
+impl ValidateResponse {
+    /// The scopes of the token
+    pub fn scope_set(&self) -> ScopeSet {
+        self.scopes.iter().flatten().map(String::as_str).collect()
+    }
+
+    /// How long until the token expires, or `None` if it does not
+    pub fn expires_after(&self) -> Option<Duration> {
+        match self.expires_in {
+            0 => None,
+            secs => Some(Duration::from_secs(secs)),
+        }
+    }
+
+    /// Build the app access token that was validated
+    pub fn app_token<S: Into<String>>(&self, token: S) -> ClientAuthToken {
+        let token = ClientAuthToken::new(token.into(), self.client_id.clone())
+            .with_scopes(self.scope_set());
+        match self.expires_after() {
+            Some(expires_in) => token.with_expiry(expires_in),
+            None => token,
+        }
+    }
+
+    /// Build the user access token that was validated, with the user it acts for
+    pub fn user_token<S: Into<String>>(
+        &self,
+        token: S,
+        refresh_token: Option<String>,
+    ) -> UserAuthToken {
+        let mut token = UserAuthToken::new(
+            token.into(),
+            refresh_token,
+            self.client_id.clone(),
+            self.scope_set(),
+        );
+        token.user_id = self.user_id.clone();
+        token.login = self.login.clone();
+        token
+    }
+}
@@ -0,0 +1,181 @@
+//! App access token shared between tasks, renewed in the background before it expires
+
+use std::sync::{Arc, Weak};
+use std::time::Duration;
+
+use reqwest::Client as RClient;
+
+use super::refresh::TokenCell;
+use super::{request_app_token, Client, ClientConfig, ClientState, RequestError};
+use crate::auth::client_credentials::ClientAuthToken;
+use crate::auth::{ClientId, ClientSecret};
+
+/// How long to wait before trying again when the token could not be renewed
+const RENEW_RETRY: Duration = Duration::from_secs(30);
+
+/// Shortest wait between two renewals, for tokens that do not outlive the refresh margin
+const MIN_RENEW_WAIT: Duration = Duration::from_secs(30);
+
+/// Keeps a fresh app access token for any number of tasks, see
+/// [`Client::app_token_manager`]
+///
+/// Clones share the same token, so the manager can be cloned into every task that needs
+/// it. With [`AppTokenManager::spawn_renewal`] the token is renewed in the background
+/// before it expires, see [`super::ClientBuilder::refresh_margin`].
+///
+/// ```no_run
+/// # use twitch_api_rs::client::Client;
+/// # #[tokio::main]
+/// # async fn main() {
+/// # let (client_id, client_secret) = (String::new(), String::new());
+/// let tokens = Client::new(reqwest::Client::new())
+///     .app_token_manager(client_id.into(), client_secret.into())
+///     .await
+///     .expect("Could not get app token");
+/// tokens.spawn_renewal();
+///
+/// let task_tokens = tokens.clone();
+/// tokio::spawn(async move {
+///     eprintln!("Using token {}", task_tokens.token().token);
+/// });
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct AppTokenManager {
+    inner: Arc<Inner>,
+}
+
+#[derive(Debug)]
+struct Inner {
+    client: Arc<RClient>,
+    config: ClientConfig,
+    secret: ClientSecret,
+    /// The expiry of the cell is not used, the token keeps track of its own
+    token: TokenCell<Arc<ClientAuthToken>>,
+}
+
+impl<T: ClientState> Client<T> {
+    /// Get an app access token that can be shared between tasks, with the settings of
+    /// this client
+    ///
+    /// If a token store is set and has a token for this client that is not about to
+    /// expire, it is used instead of requesting a new one.
+    pub async fn app_token_manager(
+        &self,
+        id: ClientId,
+        secret: ClientSecret,
+    ) -> Result<AppTokenManager, RequestError> {
+        let client = &self.common.client;
+        let config = &self.common.config;
+
+        let token = match config.load_fresh_app_token(&id) {
+            Some(token) => token,
+            None => request_app_token(client, config, id, &secret).await?,
+        };
+
+        Ok(AppTokenManager {
+            inner: Arc::new(Inner {
+                client: client.clone(),
+                config: config.clone(),
+                secret,
+                token: TokenCell::new(Arc::new(token), None),
+            }),
+        })
+    }
+}
+
+impl AppTokenManager {
+    /// The current token, which may be about to expire, see [`AppTokenManager::fresh_token`]
+    pub fn token(&self) -> Arc<ClientAuthToken> {
+        self.inner.token.get()
+    }
+
+    /// The current token, renewed first if it expires within the refresh margin
+    pub async fn fresh_token(&self) -> Result<Arc<ClientAuthToken>, RequestError> {
+        if self
+            .token()
+            .expires_within(self.inner.config.refresh_margin())
+        {
+            self.renew().await?;
+        }
+
+        Ok(self.token())
+    }
+
+    /// Replace the current token with a new one
+    ///
+    /// If another task renews the token at the same time only one new token is requested.
+    pub async fn renew(&self) -> Result<(), RequestError> {
+        let (_, generation) = self.inner.token.snapshot();
+        self.inner.renew(generation).await
+    }
+
+    /// Renew the token in the background shortly before it expires
+    ///
+    /// The task stops the next time it wakes up after every clone of the manager was
+    /// dropped, or once the token is not known to expire. Renewals are at least 30 seconds
+    /// apart, failed renewals are logged and tried again after 30 seconds.
+    pub fn spawn_renewal(&self) -> tokio::task::JoinHandle<()> {
+        let inner = Arc::downgrade(&self.inner);
+
+        tokio::spawn(async move {
+            let mut wait = match next_renewal(&inner) {
+                Some(wait) => wait,
+                None => return,
+            };
+
+            loop {
+                tokio::time::sleep(wait).await;
+
+                let inner = match inner.upgrade() {
+                    Some(inner) => inner,
+                    None => return,
+                };
+
+                let (_, generation) = inner.token.snapshot();
+                wait = match inner.renew(generation).await {
+                    Ok(()) => match inner.renewal_wait() {
+                        Some(wait) => wait.max(MIN_RENEW_WAIT),
+                        None => return,
+                    },
+                    Err(e) => {
+                        log::error!("Could not renew app token: {}", e);
+                        RENEW_RETRY
+                    }
+                };
+            }
+        })
+    }
+}
+
+/// How long until the token of `inner` should be renewed, `None` if it does not expire
+/// or the manager was dropped
+fn next_renewal(inner: &Weak<Inner>) -> Option<Duration> {
+    inner.upgrade()?.renewal_wait()
+}
+
+impl Inner {
+    /// How long until the current token is within the refresh margin, `None` if it does
+    /// not expire
+    fn renewal_wait(&self) -> Option<Duration> {
+        let left = self.token.get().expires_in()?;
+        Some(left.saturating_sub(self.config.refresh_margin()))
+    }
+
+    /// Replace the token of `generation`, unless another task already did
+    async fn renew(&self, generation: u64) -> Result<(), RequestError> {
+        self.token
+            .refresh(generation, |current| async move {
+                let token = request_app_token(
+                    &self.client,
+                    &self.config,
+                    current.client_id.clone(),
+                    &self.secret,
+                )
+                .await?;
+
+                Ok((Arc::new(token), None))
+            })
+            .await
+    }
+}
@@ -0,0 +1,446 @@
+//! Transport and behaviour configuration for a [`Client`]
+
+use reqwest::header::{HeaderMap, HeaderName, HeaderValue, AUTHORIZATION};
+use std::sync::Arc;
+use std::time::Duration;
+
+use crate::auth::client_credentials::ClientAuthToken;
+use crate::auth::store::{StoredToken, TokenKey, TokenStore};
+use crate::auth::ClientId;
+
+use super::{Client, ClientStateCommon, Unauthorized};
+
+/// Where endpoints of the helix api are hosted
+pub const DEFAULT_API_BASE: &str = "https://api.twitch.tv/helix";
+
+/// Where the endpoints for authorization are hosted
+pub const DEFAULT_AUTH_BASE: &str = "https://id.twitch.tv/oauth2";
+
+/// Query parameters that are not written to the log when redaction is on
+const SENSITIVE_PARAMETERS: &[&str] = &[
+    "client_secret",
+    "code",
+    "refresh_token",
+    "access_token",
+    "token",
+    "device_code",
+];
+
+/// Builder for a [`Client`], see [`Client::builder`]
+///
+/// ```
+/// # use std::time::Duration;
+/// # use twitch_api_rs::client::*;
+/// let client = Client::builder()
+///     .user_agent("my-bot/1.0")
+///     .timeout(Duration::from_secs(10))
+///     .retry_policy(RetryPolicy {
+///         max_retries: 3,
+///         backoff: Duration::from_millis(250),
+///     })
+///     .rate_limit(RateLimit::Error)
+///     .build()
+///     .expect("Could not build client");
+/// ```
+#[derive(Debug)]
+pub struct ClientBuilder {
+    reqwest: reqwest::ClientBuilder,
+    config: ClientConfig,
+}
+
+impl ClientBuilder {
+    pub(super) fn new() -> Self {
+        Self {
+            reqwest: reqwest::Client::builder(),
+            config: ClientConfig::default(),
+        }
+    }
+
+    /// Set the `User-Agent` header sent with every request
+    pub fn user_agent<S: Into<String>>(mut self, user_agent: S) -> Self {
+        self.reqwest = self.reqwest.user_agent(user_agent.into());
+        self
+    }
+
+    /// Set a header that is sent with every request
+    pub fn default_header(mut self, name: HeaderName, value: HeaderValue) -> Self {
+        self.config.default_headers.insert(name, value);
+        self
+    }
+
+    /// Set the base url of the helix api, defaults to [`DEFAULT_API_BASE`]
+    ///
+    /// Useful for pointing the client at a mock server
+    pub fn api_base<S: Into<String>>(mut self, base: S) -> Self {
+        self.config.api_base = base.into().trim_end_matches('/').to_string();
+        self
+    }
+
+    /// Set the base url of the authorization endpoints, defaults to [`DEFAULT_AUTH_BASE`]
+    pub fn auth_base<S: Into<String>>(mut self, base: S) -> Self {
+        self.config.auth_base = base.into().trim_end_matches('/').to_string();
+        self
+    }
+
+    /// Set the timeout for each request, from connecting until the response body is read
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.reqwest = self.reqwest.timeout(timeout);
+        self
+    }
+
+    /// Set the timeout for connecting to twitch
+    pub fn connect_timeout(mut self, timeout: Duration) -> Self {
+        self.reqwest = self.reqwest.connect_timeout(timeout);
+        self
+    }
+
+    /// Send all requests through a proxy
+    pub fn proxy(mut self, proxy: reqwest::Proxy) -> Self {
+        self.reqwest = self.reqwest.proxy(proxy);
+        self
+    }
+
+    /// Set which requests are retried and how often, see [`RetryPolicy`]
+    pub fn retry_policy(mut self, retry: RetryPolicy) -> Self {
+        self.config.retry = retry;
+        self
+    }
+
+    /// Set what happens when twitch rate limits a request, see [`RateLimit`]
+    pub fn rate_limit(mut self, rate_limit: RateLimit) -> Self {
+        self.config.rate_limit = rate_limit;
+        self
+    }
+
+    /// Set how requests and responses are logged, see [`Logging`]
+    pub fn logging(mut self, logging: Logging) -> Self {
+        self.config.logging = logging;
+        self
+    }
+
+    /// Set how long before its token expires an authorized client gets a new one,
+    /// defaults to one minute
+    pub fn refresh_margin(mut self, margin: Duration) -> Self {
+        self.config.refresh_margin = margin;
+        self
+    }
+
+    /// Load tokens from `store` instead of requesting new ones, and save every new token
+    /// to it, see [`crate::auth::store`]
+    pub fn token_store<S: TokenStore + 'static>(mut self, store: S) -> Self {
+        self.config.token_store = Some(Arc::new(store));
+        self
+    }
+
+    /// Build the client
+    pub fn build(self) -> Result<Client<Unauthorized>, reqwest::Error> {
+        let client = self
+            .reqwest
+            .default_headers(self.config.default_headers.clone())
+            .build()?;
+
+        Ok(Client::from_common(ClientStateCommon::new(
+            client,
+            self.config,
+        )))
+    }
+}
+
+/// Settings used by a [`Client`] for every request, see [`ClientBuilder`]
+#[derive(Debug, Clone)]
+pub struct ClientConfig {
+    default_headers: HeaderMap,
+    api_base: String,
+    auth_base: String,
+    retry: RetryPolicy,
+    rate_limit: RateLimit,
+    logging: Logging,
+    refresh_margin: Duration,
+    token_store: Option<Arc<dyn TokenStore>>,
+}
+
+impl Default for ClientConfig {
+    fn default() -> Self {
+        Self {
+            default_headers: HeaderMap::new(),
+            api_base: DEFAULT_API_BASE.to_string(),
+            auth_base: DEFAULT_AUTH_BASE.to_string(),
+            retry: RetryPolicy::default(),
+            rate_limit: RateLimit::default(),
+            logging: Logging::default(),
+            refresh_margin: Duration::from_secs(60),
+            token_store: None,
+        }
+    }
+}
+
+impl ClientConfig {
+    /// Base url of the helix api
+    pub fn api_base(&self) -> &str {
+        &self.api_base
+    }
+
+    /// Base url of the authorization endpoints
+    pub fn auth_base(&self) -> &str {
+        &self.auth_base
+    }
+
+    /// The retry policy used for requests
+    pub fn retry(&self) -> &RetryPolicy {
+        &self.retry
+    }
+
+    /// What happens when a request is rate limited
+    pub fn rate_limit(&self) -> &RateLimit {
+        &self.rate_limit
+    }
+
+    /// How requests are logged
+    pub fn logging(&self) -> &Logging {
+        &self.logging
+    }
+
+    /// How long before its token expires an authorized client gets a new one
+    pub fn refresh_margin(&self) -> Duration {
+        self.refresh_margin
+    }
+
+    /// Where tokens are loaded from and saved to, if anywhere
+    pub fn token_store(&self) -> Option<&dyn TokenStore> {
+        self.token_store.as_deref()
+    }
+
+    /// Load the token saved under `key` from the token store if there is one, logging any
+    /// failure
+    pub(crate) fn load_token(&self, key: &TokenKey) -> Option<StoredToken> {
+        match self.token_store.as_ref()?.load(key) {
+            Ok(token) => token,
+            Err(e) => {
+                log::warn!("Could not load token {} from the token store: {}", key, e);
+                None
+            }
+        }
+    }
+
+    /// Load the app token saved for the client `id` from the token store, if there is one
+    /// that is not about to expire
+    pub(crate) fn load_fresh_app_token(&self, id: &ClientId) -> Option<ClientAuthToken> {
+        let stored = self.load_token(&TokenKey::App(id.clone()))?;
+
+        if stored
+            .expires_in()
+            .is_none_or(|e| e > self.refresh_margin())
+        {
+            Some(stored.to_app())
+        } else {
+            None
+        }
+    }
+
+    /// Save `token` to the token store if there is one, logging any failure
+    pub(crate) fn save_token(&self, key: &TokenKey, token: &StoredToken) {
+        if let Some(store) = &self.token_store {
+            if let Err(e) = store.save(key, token) {
+                log::warn!("Could not save token {} to the token store: {}", key, e);
+            }
+        }
+    }
+
+    /// Remove the token saved under `key` from the token store if there is one, logging
+    /// any failure
+    pub(crate) fn delete_token(&self, key: &TokenKey) {
+        if let Some(store) = &self.token_store {
+            if let Err(e) = store.delete(key) {
+                log::warn!("Could not delete token {} from the token store: {}", key, e);
+            }
+        }
+    }
+
+    /// Get the url that a request to `endpoint` should be made to, replacing the default
+    /// base urls with the configured ones
+    pub fn url_for(&self, endpoint: &str) -> String {
+        if let Some(path) = endpoint.strip_prefix(DEFAULT_API_BASE) {
+            format!("{}{}", self.api_base, path)
+        } else if let Some(path) = endpoint.strip_prefix(DEFAULT_AUTH_BASE) {
+            format!("{}{}", self.auth_base, path)
+        } else {
+            endpoint.to_string()
+        }
+    }
+}
+
+/// Which failed requests are retried, and how long to wait between attempts
+///
+/// Requests are retried when the connection failed. Requests with an idempotent method,
+/// like `GET`, are also retried when twitch responds with a server error (5xx) or the
+/// request timed out. Other requests, like exchanging a code or refresh token, may
+/// already have been handled by twitch and are not sent again.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RetryPolicy {
+    /// The most times a single request is retried, `0` disables retries
+    pub max_retries: u32,
+
+    /// How long to wait before the first retry, doubled for each one after
+    pub backoff: Duration,
+}
+
+impl RetryPolicy {
+    /// Never retry requests
+    pub fn none() -> Self {
+        Self {
+            max_retries: 0,
+            backoff: Duration::from_secs(0),
+        }
+    }
+
+    /// How long to wait before retry number `attempt`, starting at 1
+    pub fn delay(&self, attempt: u32) -> Duration {
+        self.backoff
+            .checked_mul(2u32.saturating_pow(attempt.saturating_sub(1)))
+            .unwrap_or(Duration::MAX)
+    }
+}
+
+impl Default for RetryPolicy {
+    /// Retry once after half a second, as twitch recommends for server errors
+    fn default() -> Self {
+        Self {
+            max_retries: 1,
+            backoff: Duration::from_millis(500),
+        }
+    }
+}
+
+/// What a [`Client`] does when twitch responds that a request was rate limited (429)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RateLimit {
+    /// Return the error from twitch
+    Error,
+
+    /// Wait until the rate limit resets, from the `Ratelimit-Reset` header, then send
+    /// the request once more. If the reset is further away than `max_wait` then return
+    /// the error instead.
+    Wait {
+        /// Longest time to wait for the rate limit to reset
+        max_wait: Duration,
+    },
+}
+
+impl RateLimit {
+    /// How long to wait before retrying a rate limited response, if at all
+    pub(crate) fn wait_for(&self, resp: &reqwest::Response) -> Option<Duration> {
+        let max_wait = match self {
+            Self::Error => return None,
+            Self::Wait { max_wait } => *max_wait,
+        };
+
+        let reset = resp
+            .headers()
+            .get("Ratelimit-Reset")?
+            .to_str()
+            .ok()?
+            .parse::<u64>()
+            .ok()?;
+
+        let now = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .ok()?;
+
+        let wait = Duration::from_secs(reset).saturating_sub(now);
+
+        if wait <= max_wait {
+            Some(wait)
+        } else {
+            None
+        }
+    }
+}
+
+impl Default for RateLimit {
+    fn default() -> Self {
+        Self::Wait {
+            max_wait: Duration::from_secs(60),
+        }
+    }
+}
+
+/// How requests and responses are written to the [`log`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Logging {
+    /// The level requests and responses are logged at, `None` disables logging
+    pub level: Option<log::Level>,
+
+    /// Replace tokens and secrets with `<redacted>` in logged headers and parameters
+    pub redact: bool,
+}
+
+impl Default for Logging {
+    fn default() -> Self {
+        Self {
+            level: Some(log::Level::Info),
+            redact: true,
+        }
+    }
+}
+
+impl Logging {
+    /// Log a request that is about to be sent
+    pub fn log_request(&self, req: &reqwest::Request) {
+        let level = match self.level {
+            Some(level) if log::log_enabled!(level) => level,
+            _ => return,
+        };
+
+        let url = self.redact_url(req.url());
+        let mut headers = req.headers().clone();
+
+        if self.redact && headers.contains_key(AUTHORIZATION) {
+            headers.insert(AUTHORIZATION, HeaderValue::from_static("<redacted>"));
+        }
+
+        log::log!(
+            level,
+            "Making request {} {} with headers {:?}",
+            req.method(),
+            url,
+            headers
+        );
+    }
+
+    /// Log a response that was received
+    pub fn log_response(&self, resp: &reqwest::Response) {
+        let level = match self.level {
+            Some(level) if log::log_enabled!(level) => level,
+            _ => return,
+        };
+
+        log::log!(
+            level,
+            "Got response {} from {}",
+            resp.status(),
+            self.redact_url(resp.url())
+        );
+    }
+
+    /// Copy `url`, replacing the value of every sensitive query parameter when redacting
+    fn redact_url(&self, url: &reqwest::Url) -> reqwest::Url {
+        let mut url = url.clone();
+
+        if self.redact && url.query().is_some() {
+            let pairs: Vec<(String, String)> = url
+                .query_pairs()
+                .map(|(k, v)| {
+                    if SENSITIVE_PARAMETERS.contains(&&*k) {
+                        (k.into_owned(), "<redacted>".to_string())
+                    } else {
+                        (k.into_owned(), v.into_owned())
+                    }
+                })
+                .collect();
+
+            url.query_pairs_mut().clear().extend_pairs(pairs);
+        }
+
+        url
+    }
+}
@@ -0,0 +1,183 @@
+//! Endpoint handles returned by an authorized [`Client`]
+
+use async_trait::async_trait;
+
+use super::{AuthorizedState, Client};
+use crate::api::{ApiResult, TwitchApi};
+use crate::requests::SerializableRequest;
+use crate::resource::channels::get_channel_information::*;
+use crate::resource::clips::get_clips::*;
+use crate::resource::users::get_users::*;
+use crate::values::broadcasters::BroadcasterId;
+use crate::values::clips::ClipId;
+use crate::values::games::GameId;
+use crate::values::users::{UserId, UserName};
+
+impl<T: AuthorizedState> Client<T> {
+    /// Endpoints of the `Users` resource
+    pub fn users(&self) -> Users<'_, T> {
+        Users { client: self }
+    }
+
+    /// Endpoints of the `Clips` resource
+    pub fn clips(&self) -> Clips<'_, T> {
+        Clips { client: self }
+    }
+
+    /// Endpoints of the `Channels` resource
+    pub fn channels(&self) -> Channels<'_, T> {
+        Channels { client: self }
+    }
+}
+
+/// Requests to do with the `Users` resource, see [`Client::users`]
+#[derive(Debug)]
+pub struct Users<'c, T: AuthorizedState> {
+    pub(super) client: &'c Client<T>,
+}
+
+impl<'c, T: AuthorizedState> Users<'c, T> {
+    /// Get the user with the login name `login`, if they exist
+    pub async fn by_login<L: Into<UserName>>(
+        &self,
+        login: L,
+    ) -> ApiResult<Option<UserDescription>> {
+        Ok(self.by_logins(Some(login)).await?.pop())
+    }
+
+    /// Get the users with the login names `logins`, at most 100
+    pub async fn by_logins<C, L>(&self, logins: C) -> ApiResult<Vec<UserDescription>>
+    where
+        C: IntoIterator<Item = L>,
+        L: Into<UserName>,
+    {
+        let job = GetUsersJob {
+            logins: logins.into_iter().map(Into::into).collect(),
+            ..GetUsersJob::default()
+        };
+        Ok(self.client.get_users(job).await?.users)
+    }
+
+    /// Get the user with the id `id`, if they exist
+    pub async fn by_id<I: Into<UserId>>(&self, id: I) -> ApiResult<Option<UserDescription>> {
+        Ok(self.by_ids(Some(id)).await?.pop())
+    }
+
+    /// Get the users with the ids `ids`, at most 100
+    pub async fn by_ids<C, I>(&self, ids: C) -> ApiResult<Vec<UserDescription>>
+    where
+        C: IntoIterator<Item = I>,
+        I: Into<UserId>,
+    {
+        let job = GetUsersJob {
+            ids: ids.into_iter().map(Into::into).collect(),
+            ..GetUsersJob::default()
+        };
+        Ok(self.client.get_users(job).await?.users)
+    }
+}
+
+/// Requests to do with the `Clips` resource, see [`Client::clips`]
+#[derive(Debug)]
+pub struct Clips<'c, T: AuthorizedState> {
+    client: &'c Client<T>,
+}
+
+impl<'c, T: AuthorizedState> Clips<'c, T> {
+    /// Get the first page of clips made from a broadcaster's channel
+    ///
+    /// Use [`Clips::get`] for more control over the request
+    pub async fn for_broadcaster<B: Into<BroadcasterId>>(
+        &self,
+        broadcaster_id: B,
+    ) -> ApiResult<GetClipsResponse> {
+        self.get(GetClipsJob {
+            query_type: QueryType::BroadCasterId(broadcaster_id.into()),
+            ..GetClipsJob::default()
+        })
+        .await
+    }
+
+    /// Get the first page of clips made of a game
+    ///
+    /// Use [`Clips::get`] for more control over the request
+    pub async fn for_game<G: Into<GameId>>(&self, game_id: G) -> ApiResult<GetClipsResponse> {
+        self.get(GetClipsJob {
+            query_type: QueryType::GameId(game_id.into()),
+            ..GetClipsJob::default()
+        })
+        .await
+    }
+
+    /// Get specific clips by their ids, at most 100
+    pub async fn by_ids<C: Into<ClipId>>(&self, ids: Vec<C>) -> ApiResult<GetClipsResponse> {
+        self.get(GetClipsJob {
+            query_type: QueryType::ClipId(ids.into_iter().map(Into::into).collect()),
+            ..GetClipsJob::default()
+        })
+        .await
+    }
+
+    /// Make a request described by `job`, see [`GetClipsRequest::to_job`]
+    pub async fn get(&self, job: GetClipsJob) -> ApiResult<GetClipsResponse> {
+        self.client
+            .send_authorized(|token| {
+                let mut req = GetClipsRequest::from_job(job.clone());
+                req.set_auth(token);
+                req
+            })
+            .await
+    }
+}
+
+/// Requests to do with the `Channels` resource, see [`Client::channels`]
+#[derive(Debug)]
+pub struct Channels<'c, T: AuthorizedState> {
+    client: &'c Client<T>,
+}
+
+impl<'c, T: AuthorizedState> Channels<'c, T> {
+    /// Get the channel information for a broadcaster, if they exist
+    pub async fn info<B: Into<BroadcasterId>>(
+        &self,
+        broadcaster_id: B,
+    ) -> ApiResult<Option<ChannelInformation>> {
+        let job = GetChannelInformationJob {
+            broadcaster_id: Some(broadcaster_id.into()),
+        };
+        Ok(self
+            .client
+            .get_channel_information(job)
+            .await?
+            .channels
+            .pop())
+    }
+}
+
+#[async_trait]
+impl<T: AuthorizedState> TwitchApi for Client<T> {
+    async fn get_users(&self, job: GetUsersJob) -> ApiResult<GetUsersResponse> {
+        self.send_authorized(|token| {
+            let mut req = GetUsersRequest::from_job(job.clone());
+            req.set_auth(token);
+            req
+        })
+        .await
+    }
+
+    async fn get_clips(&self, job: GetClipsJob) -> ApiResult<GetClipsResponse> {
+        self.clips().get(job).await
+    }
+
+    async fn get_channel_information(
+        &self,
+        job: GetChannelInformationJob,
+    ) -> ApiResult<GetChannelInformationResponse> {
+        self.send_authorized(|token| {
+            let mut req = GetChannelInformationRequest::from_job(job.clone());
+            req.set_auth(token);
+            req
+        })
+        .await
+    }
+}
@@ -0,0 +1,619 @@
+//! Typed client that keeps its authorization and [`reqwest::Client`] for every request
+//!
+//! A [`Client`] starts [`Unauthorized`], and moves to an authorized state once it has
+//! a token, either [`ClientCredentials`] for an app access token or [`UserToken`] to act
+//! on behalf of a user. Endpoints are then available through [`Client::users`], [`Client::clips`]
+//! and [`Client::channels`].
+//!
+//! Authorized clients renew their token when it is about to expire, see
+//! [`ClientBuilder::refresh_margin`], or when twitch rejects it. Requests made through
+//! the endpoint handles are then sent once more with the new token.
+//!
+//! Applications acting for many users at once can keep their tokens in a [`TokenPool`].
+//! An app access token can be shared between tasks with an [`AppTokenManager`].
+//! Tokens can be kept across restarts with a [`crate::auth::store::TokenStore`], see
+//! [`ClientBuilder::token_store`].
+//!
+//! ```no_run
+//! # use twitch_api_rs::client::Client;
+//! # #[tokio::main]
+//! # async fn main() {
+//! # let (client_id, client_secret) = (String::new(), String::new());
+//! let client = match Client::new(reqwest::Client::new())
+//!     .client_auth(client_id.into(), client_secret.into())
+//!     .await
+//! {
+//!     Ok(client) => client,
+//!     Err((e, _)) => panic!("Could not authorize client for reason {}", e),
+//! };
+//!
+//! let user = client
+//!     .users()
+//!     .by_login("TheHoodlum12")
+//!     .await
+//!     .expect("Could not get user")
+//!     .expect("User does not exist");
+//!
+//! for clip in client.clips().for_broadcaster(user.id).await.unwrap().clips {
+//!     eprintln!("Found clip titled {:?}", clip.title);
+//! }
+//! # }
+//! ```
+
+mod app_token;
+mod config;
+mod endpoints;
+mod pool;
+mod refresh;
+mod user;
+
+pub use app_token::*;
+pub use config::*;
+pub use endpoints::*;
+pub use pool::*;
+pub use user::*;
+
+use std::{fmt::Display, sync::Arc, time::Duration};
+
+use async_trait::async_trait;
+use reqwest::Client as RClient;
+use thiserror::Error;
+
+use crate::{
+    auth::{
+        client_credentials::{ClientAuthRequest, ClientAuthToken},
+        device_code::DeviceTokenErrorCodes,
+        refresh_token::RefreshTokenErrorCodes,
+        revoke::{RevokeErrorCodes, RevokeRequest},
+        store::{StoredToken, TokenKey},
+        validate::{ValidateRequest, ValidateResponse},
+        AppOrUserToken, ClientId, ClientSecret,
+    },
+    crate_prelude::FailureStatus,
+    requests::{self, CommonResponseCodes, Request},
+};
+
+use refresh::TokenCell;
+
+#[derive(Debug)]
+/// Returned when a [`Client`] could not be authorized
+pub struct RequestError {
+    ty: RequestErrorType,
+}
+
+impl Display for RequestError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.ty)
+    }
+}
+
+impl std::error::Error for RequestError {}
+
+impl<T> From<T> for RequestError
+where
+    RequestErrorType: From<T>,
+{
+    fn from(ty: T) -> Self {
+        Self { ty: ty.into() }
+    }
+}
+
+impl RequestError {
+    /// Get the reason this error occurred
+    pub fn kind(&self) -> &RequestErrorType {
+        &self.ty
+    }
+}
+
+#[derive(Debug, Error)]
+/// The reason for a [`RequestError`]
+pub enum RequestErrorType {
+    #[error("{from}")]
+    /// Reqwest could not complete the request
+    ReqwestError {
+        #[from]
+        #[allow(missing_docs)]
+        from: reqwest::Error,
+    },
+
+    #[error("{from}")]
+    /// Twitch denied the request
+    FailureStatusNum {
+        #[from]
+        #[allow(missing_docs)]
+        from: FailureStatus<u16>,
+    },
+
+    #[error("{from}")]
+    /// The authorization request could not be completed
+    AuthRequestError {
+        #[from]
+        #[allow(missing_docs)]
+        from: requests::RequestError<CommonResponseCodes>,
+    },
+
+    #[error("{from}")]
+    /// The user did not authorize a device code
+    DeviceAuthError {
+        #[from]
+        #[allow(missing_docs)]
+        from: requests::RequestError<DeviceTokenErrorCodes>,
+    },
+
+    #[error("{from}")]
+    /// The user token could not be refreshed
+    RefreshError {
+        #[from]
+        #[allow(missing_docs)]
+        from: requests::RequestError<RefreshTokenErrorCodes>,
+    },
+
+    #[error("{from}")]
+    /// The token could not be revoked
+    RevokeError {
+        #[from]
+        #[allow(missing_docs)]
+        from: requests::RequestError<RevokeErrorCodes>,
+    },
+}
+
+/// Client for the twitch api, tracking what kind of authorization it has in `T`
+///
+/// See module level documentation for usage.
+#[derive(Debug)]
+pub struct Client<T: ClientState> {
+    common: Box<ClientStateCommon>,
+    current: T,
+}
+
+#[derive(Debug)]
+struct ClientStateCommon {
+    client: Arc<RClient>,
+    config: ClientConfig,
+}
+
+impl ClientStateCommon {
+    fn new(client: RClient, config: ClientConfig) -> Self {
+        Self {
+            client: Arc::new(client),
+            config,
+        }
+    }
+}
+
+/// [`Client`] state before any authorization has been requested
+#[derive(Debug)]
+pub struct Unauthorized {}
+
+/// [`Client`] state holding an app access token from the
+/// [`crate::auth::client_credentials`] flow
+#[derive(Debug)]
+pub struct ClientCredentials {
+    token: TokenCell<Arc<ClientAuthToken>>,
+    secret: ClientSecret,
+}
+
+impl Client<Unauthorized> {
+    /// Create a new client with the default configuration, see [`Client::builder`] to
+    /// configure it
+    pub fn new(client: RClient) -> Client<Unauthorized> {
+        Self::from_common(ClientStateCommon::new(client, ClientConfig::default()))
+    }
+
+    /// Get a builder to configure a new client
+    pub fn builder() -> ClientBuilder {
+        ClientBuilder::new()
+    }
+
+    fn from_common(common: ClientStateCommon) -> Self {
+        Client {
+            common: Box::new(common),
+            current: Unauthorized {},
+        }
+    }
+
+    /// Get an app access token with the client credentials flow
+    ///
+    /// If a token store is set and has a token for this client that is not about to
+    /// expire, it is used instead of requesting a new one.
+    ///
+    /// Returns self with the error if authorization failed so it may be tried again
+    pub async fn client_auth(
+        self,
+        id: ClientId,
+        secret: ClientSecret,
+    ) -> Result<Client<ClientCredentials>, (RequestError, Self)> {
+        let config = &self.common.config;
+
+        let token = match config.load_fresh_app_token(&id) {
+            Some(token) => token,
+            None => match request_app_token(&self.common.client, config, id, &secret).await {
+                Ok(token) => token,
+                Err(e) => return Err((e.into(), self)),
+            },
+        };
+        let expires_in = token.expires_in();
+
+        Ok(Client {
+            common: self.common,
+            current: ClientCredentials {
+                token: TokenCell::new(Arc::new(token), expires_in),
+                secret,
+            },
+        })
+    }
+}
+
+impl<T: ClientState> Client<T> {
+    /// The [`reqwest::Client`] used to make requests
+    pub fn reqwest(&self) -> &RClient {
+        &self.common.client
+    }
+
+    /// The settings this client uses for every request
+    pub fn config(&self) -> &ClientConfig {
+        &self.common.config
+    }
+
+    /// Make a request with the settings of this client, retrying and waiting on rate
+    /// limits as configured
+    async fn execute<R>(
+        &self,
+        req: &R,
+    ) -> Result<R::Response, requests::RequestError<R::ErrorCodes>>
+    where
+        R: Request + Sync,
+    {
+        execute(&self.common.client, &self.common.config, req).await
+    }
+}
+
+/// Make a request with `client`, retrying and waiting on rate limits as set in `config`
+async fn execute<R>(
+    client: &RClient,
+    config: &ClientConfig,
+    req: &R,
+) -> Result<R::Response, requests::RequestError<R::ErrorCodes>>
+where
+    R: Request + Sync,
+{
+    req.ready()?;
+    let resp = send_with_retries(client, config, req).await?;
+    requests::parse_response::<R>(resp).await
+}
+
+/// Send a request until it gets a response that should not be retried
+///
+/// Requests that failed to connect or were rate limited never reached twitch and are
+/// always retried. Timeouts and server errors are only retried for idempotent methods,
+/// as a `POST` that did reach twitch may have used up a single use code or refresh token.
+async fn send_with_retries<R>(
+    client: &RClient,
+    config: &ClientConfig,
+    req: &R,
+) -> Result<reqwest::Response, requests::RequestError<R::ErrorCodes>>
+where
+    R: Request + Sync,
+{
+    let url = config.url_for(R::ENDPOINT);
+
+    let idempotent = R::METHOD.is_idempotent();
+
+    let mut retries = 0;
+    let mut waited = false;
+
+    loop {
+        let built = requests::build_request(req, client, &url).build()?;
+        config.logging().log_request(&built);
+
+        let resp = match client.execute(built).await {
+            Ok(resp) => resp,
+            Err(e)
+                if (e.is_connect() || (idempotent && e.is_timeout()))
+                    && retries < config.retry().max_retries =>
+            {
+                retries += 1;
+                tokio::time::sleep(config.retry().delay(retries)).await;
+                continue;
+            }
+            Err(e) => return Err(e.into()),
+        };
+
+        config.logging().log_response(&resp);
+
+        if resp.status() == reqwest::StatusCode::TOO_MANY_REQUESTS && !waited {
+            if let Some(wait) = config.rate_limit().wait_for(&resp) {
+                log::warn!("Rate limited, waiting {:?} before retrying", wait);
+                waited = true;
+                tokio::time::sleep(wait).await;
+                continue;
+            }
+        }
+
+        if idempotent && resp.status().is_server_error() && retries < config.retry().max_retries {
+            retries += 1;
+            tokio::time::sleep(config.retry().delay(retries)).await;
+            continue;
+        }
+
+        return Ok(resp);
+    }
+}
+
+impl<T: AuthorizedState> Client<T> {
+    /// The token sent with requests made by this client
+    pub fn token(&self) -> T::Token {
+        self.current.token()
+    }
+
+    /// How long until the current token expires, if known
+    pub fn token_expires_in(&self) -> Option<Duration> {
+        self.current.cell().expires_in()
+    }
+
+    /// Call `on_refresh` with the new token every time the token is refreshed, for
+    /// example to store it
+    pub fn on_token_refresh<F>(mut self, on_refresh: F) -> Self
+    where
+        F: Fn(&T::Token) + Send + Sync + 'static,
+    {
+        self.current.cell_mut().set_on_refresh(Box::new(on_refresh));
+        self
+    }
+
+    /// Get a new token now, instead of waiting for the current one to expire
+    pub async fn refresh_token(&self) -> Result<(), RequestError> {
+        let (_, generation) = self.current.cell().snapshot();
+        self.refresh_from(generation).await
+    }
+
+    /// Make any request with the settings of this client
+    ///
+    /// Authorization should already be set on the request, see [`Client::token`]. The
+    /// request is not sent again if the token was rejected, see [`Client::send_authorized`]
+    pub async fn send<R>(
+        &self,
+        req: &R,
+    ) -> Result<R::Response, requests::RequestError<R::ErrorCodes>>
+    where
+        R: Request + Sync,
+    {
+        self.execute(req).await
+    }
+
+    /// Make the request returned by `build` with the current token
+    ///
+    /// The token is refreshed first if it is about to expire. If twitch rejects the token
+    /// it is refreshed and `build` is called again to send the request once more.
+    pub async fn send_authorized<R, F>(
+        &self,
+        build: F,
+    ) -> Result<R::Response, requests::RequestError<R::ErrorCodes>>
+    where
+        R: Request + Sync,
+        F: Fn(T::Token) -> R,
+    {
+        let cell = self.current.cell();
+
+        if cell.expires_within(self.common.config.refresh_margin()) {
+            let (_, generation) = cell.snapshot();
+            self.refresh_from(generation)
+                .await
+                .map_err(|e| requests::RequestError::UnknownError(Box::new(e)))?;
+        }
+
+        let (token, generation) = cell.snapshot();
+        let req = build(token);
+        req.ready()?;
+
+        let resp = send_with_retries(&self.common.client, &self.common.config, &req).await?;
+        if resp.status() != reqwest::StatusCode::UNAUTHORIZED {
+            return requests::parse_response::<R>(resp).await;
+        }
+
+        log::warn!("Token was rejected, refreshing it before sending the request again");
+        self.refresh_from(generation)
+            .await
+            .map_err(|e| requests::RequestError::UnknownError(Box::new(e)))?;
+
+        self.execute(&build(cell.get())).await
+    }
+
+    /// Check the current token with twitch, refreshing it if it is no longer valid
+    ///
+    /// Returns what twitch knows about the token that was checked, or the error if it
+    /// could not be refreshed
+    pub async fn validate(&self) -> Result<ValidateResponse, RequestError> {
+        let (token, generation) = self.current.cell().snapshot();
+        let req = ValidateRequest::from(T::access_token(&token));
+
+        match self.execute(&req).await {
+            Err(requests::RequestError::KnownErrorStatus(FailureStatus {
+                status: CommonResponseCodes::AuthErrorCode,
+                ..
+            })) => {
+                log::warn!("Token is no longer valid, refreshing it");
+                self.refresh_from(generation).await?;
+
+                let token = self.current.cell().get();
+                Ok(self
+                    .execute(&ValidateRequest::from(T::access_token(&token)))
+                    .await?)
+            }
+            other => Ok(other?),
+        }
+    }
+
+    /// Revoke the current token and remove it from the token store, going back to an
+    /// unauthorized client
+    ///
+    /// Returns self with the error if twitch did not revoke the token
+    pub async fn revoke(self) -> Result<Client<Unauthorized>, (RequestError, Self)> {
+        let token = self.current.token();
+        let req = RevokeRequest::from((T::client_id(&token).clone(), T::access_token(&token)));
+
+        if let Err(e) = self.execute(&req).await {
+            return Err((e.into(), self));
+        }
+
+        self.common.config.delete_token(&self.current.token_key());
+        Ok(Client::from_common(*self.common))
+    }
+
+    /// Validate the token every `every` in a background task, as twitch requires for
+    /// long lived sessions, see
+    /// [`VALIDATION_INTERVAL`](crate::auth::validate::VALIDATION_INTERVAL)
+    ///
+    /// The task stops once the client is dropped. Failures are logged, and the token is
+    /// refreshed when twitch no longer accepts it.
+    pub fn spawn_validation(self: &Arc<Self>, every: Duration) -> tokio::task::JoinHandle<()>
+    where
+        T: 'static,
+    {
+        let client = Arc::downgrade(self);
+
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(every);
+            interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+
+            loop {
+                interval.tick().await;
+
+                let client = match client.upgrade() {
+                    Some(client) => client,
+                    None => return,
+                };
+
+                if let Err(e) = client.validate().await {
+                    log::error!("Could not validate token: {}", e);
+                }
+            }
+        })
+    }
+
+    /// Replace the token of `generation`, unless another task already did
+    async fn refresh_from(&self, generation: u64) -> Result<(), RequestError> {
+        let client = &*self.common.client;
+        let config = &self.common.config;
+
+        self.current
+            .cell()
+            .refresh(generation, |token| {
+                self.current.renew(client, config, token)
+            })
+            .await
+    }
+}
+
+/// The state of a [`Client`], may not be implemented outside of this crate
+pub trait ClientState: sealed::Sealed {}
+
+impl ClientState for Unauthorized {}
+impl ClientState for ClientCredentials {}
+
+/// A [`ClientState`] that holds a token, and so can make requests to endpoints
+#[async_trait]
+pub trait AuthorizedState: ClientState + Send + Sync {
+    /// The token type sent with requests
+    type Token: AppOrUserToken + Clone + Send + Sync + 'static;
+
+    /// Get the current token
+    fn token(&self) -> Self::Token {
+        self.cell().get()
+    }
+
+    #[doc(hidden)]
+    fn cell(&self) -> &TokenCell<Self::Token>;
+
+    #[doc(hidden)]
+    fn cell_mut(&mut self) -> &mut TokenCell<Self::Token>;
+
+    /// The access token of `token`, as sent to twitch
+    #[doc(hidden)]
+    fn access_token(token: &Self::Token) -> &str;
+
+    /// The client `token` was issued to
+    #[doc(hidden)]
+    fn client_id(token: &Self::Token) -> &ClientId;
+
+    /// The key the token is saved under in a token store
+    #[doc(hidden)]
+    fn token_key(&self) -> TokenKey;
+
+    /// Request a token to replace `current`, and how long until it expires
+    #[doc(hidden)]
+    async fn renew(
+        &self,
+        client: &RClient,
+        config: &ClientConfig,
+        current: Self::Token,
+    ) -> Result<(Self::Token, Option<Duration>), RequestError>;
+}
+
+#[async_trait]
+impl AuthorizedState for ClientCredentials {
+    type Token = Arc<ClientAuthToken>;
+
+    fn cell(&self) -> &TokenCell<Self::Token> {
+        &self.token
+    }
+
+    fn cell_mut(&mut self) -> &mut TokenCell<Self::Token> {
+        &mut self.token
+    }
+
+    fn access_token(token: &Self::Token) -> &str {
+        &token.token
+    }
+
+    fn client_id(token: &Self::Token) -> &ClientId {
+        &token.client_id
+    }
+
+    fn token_key(&self) -> TokenKey {
+        TokenKey::App(self.token.get().client_id.clone())
+    }
+
+    async fn renew(
+        &self,
+        client: &RClient,
+        config: &ClientConfig,
+        current: Self::Token,
+    ) -> Result<(Self::Token, Option<Duration>), RequestError> {
+        let token =
+            request_app_token(client, config, current.client_id.clone(), &self.secret).await?;
+        let expires_in = token.expires_in();
+
+        Ok((Arc::new(token), expires_in))
+    }
+}
+
+/// Get a new app access token with the client credentials flow, and save it in the token
+/// store if one is set
+async fn request_app_token(
+    client: &RClient,
+    config: &ClientConfig,
+    id: ClientId,
+    secret: &ClientSecret,
+) -> Result<ClientAuthToken, requests::RequestError<CommonResponseCodes>> {
+    let req = ClientAuthRequest::from((id.clone(), secret.clone()));
+    let token = ClientAuthToken::from_client(execute(client, config, &req).await?, id);
+
+    config.save_token(
+        &TokenKey::App(token.client_id.clone()),
+        &StoredToken::from_app(&token, token.expires_in()),
+    );
+
+    Ok(token)
+}
+
+/// Make sure that only types named here can implement ClientState
+mod sealed {
+    use super::*;
+
+    pub trait Sealed {}
+
+    impl Sealed for Unauthorized {}
+    impl Sealed for ClientCredentials {}
+    impl Sealed for UserToken {}
+}
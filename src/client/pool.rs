@@ -0,0 +1,340 @@
+//! User tokens for many channels, for applications that act on behalf of many users
+
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::{Arc, RwLock};
+use std::time::Duration;
+
+use reqwest::Client as RClient;
+use thiserror::Error;
+
+use super::refresh::TokenCell;
+use super::{execute, send_with_retries, user::refresh_user_token, Client, ClientConfig};
+use super::{ClientState, RequestError};
+use crate::auth::revoke::RevokeRequest;
+use crate::auth::scopes::{MissingScopes, ScopeRequirement};
+use crate::auth::store::{StoredToken, TokenKey};
+use crate::auth::user_token::UserAuthToken;
+use crate::auth::{AuthToken, ClientSecret};
+use crate::requests::{self, BroadcasterRequest, ErrorCodes};
+use crate::values::users::UserId;
+
+type Cell = Arc<TokenCell<Arc<UserAuthToken>>>;
+type PoolCallback = Arc<dyn Fn(&Arc<UserAuthToken>) + Send + Sync>;
+
+/// Returned when a [`TokenPool`] can not hand out a token for a user
+#[derive(Debug, Error)]
+pub enum PoolError {
+    #[error("No token stored for user {0:?}")]
+    /// The pool has no token for this user or broadcaster
+    MissingToken(UserId),
+
+    #[error("Token of user {user:?} can not be used: {missing}")]
+    /// The stored token was not granted the scopes that are needed
+    MissingScopes {
+        #[allow(missing_docs)]
+        user: UserId,
+        /// The part of the requirement the token does not meet
+        missing: MissingScopes,
+    },
+
+    #[error("Could not refresh token of user {user:?}: {from}")]
+    /// The token expired or was rejected, and could not be refreshed
+    RefreshFailed {
+        #[allow(missing_docs)]
+        user: UserId,
+        #[allow(missing_docs)]
+        from: RequestError,
+    },
+
+    #[error("Could not revoke token of user {user:?}: {from}")]
+    /// Twitch did not revoke the token, it was kept in the pool
+    RevokeFailed {
+        #[allow(missing_docs)]
+        user: UserId,
+        #[allow(missing_docs)]
+        from: RequestError,
+    },
+}
+
+impl<C: ErrorCodes> From<PoolError> for requests::RequestError<C> {
+    fn from(e: PoolError) -> Self {
+        match e {
+            PoolError::MissingScopes { missing, .. } => missing.into(),
+            e => requests::RequestError::UnknownError(Box::new(e)),
+        }
+    }
+}
+
+/// User tokens keyed by the user or broadcaster they act for, see [`Client::token_pool`]
+///
+/// Each token is refreshed on its own when it is about to expire or gets rejected,
+/// with the refresh token it was stored with.
+///
+/// ```no_run
+/// # use twitch_api_rs::client::*;
+/// # use twitch_api_rs::auth::scopes::{ScopeRequirement, ScopeSet};
+/// # use twitch_api_rs::auth::user_token::UserAuthToken;
+/// # use twitch_api_rs::requests::Request;
+/// # use twitch_api_rs::resource::channels::get_channel_information::*;
+/// # #[tokio::main]
+/// # async fn main() {
+/// # let client = Client::new(reqwest::Client::new());
+/// # let (access, refresh) = (String::new(), String::new());
+/// # let token = UserAuthToken::new(access, Some(refresh), String::new(), ScopeSet::new());
+/// let pool = client.token_pool(String::from("client_secret").into());
+/// pool.insert("477906794", token, None);
+///
+/// // The token of the broadcaster the request is for is used, channel information
+/// // needs no scopes
+/// let mut req = GetChannelInformationRequest::builder();
+/// req.set_broadcaster_id("477906794");
+///
+/// let info = pool.send(req, ScopeRequirement::none()).await;
+/// # }
+/// ```
+pub struct TokenPool {
+    client: Arc<RClient>,
+    config: ClientConfig,
+    secret: ClientSecret,
+    tokens: RwLock<HashMap<UserId, Cell>>,
+    on_refresh: Option<PoolCallback>,
+}
+
+impl<T: ClientState> Client<T> {
+    /// Create an empty pool of user tokens that shares the settings of this client
+    ///
+    /// `secret` is the secret of the client the tokens were issued to, used to refresh them
+    pub fn token_pool(&self, secret: ClientSecret) -> TokenPool {
+        TokenPool {
+            client: self.common.client.clone(),
+            config: self.common.config.clone(),
+            secret,
+            tokens: RwLock::new(HashMap::new()),
+            on_refresh: None,
+        }
+    }
+}
+
+impl TokenPool {
+    /// Call `on_refresh` with the new token every time a token in the pool is refreshed
+    ///
+    /// Only applies to tokens inserted after this is set
+    pub fn on_token_refresh<F>(mut self, on_refresh: F) -> Self
+    where
+        F: Fn(&Arc<UserAuthToken>) + Send + Sync + 'static,
+    {
+        self.on_refresh = Some(Arc::new(on_refresh));
+        self
+    }
+
+    /// Store the token for `user`, that expires after `expires_in` if known, replacing
+    /// any token the user had
+    ///
+    /// The token is also saved to the token store of the client, if it has one
+    pub fn insert<I>(&self, user: I, mut token: UserAuthToken, expires_in: Option<Duration>)
+    where
+        I: Into<UserId>,
+    {
+        let user = user.into();
+        token.user_id = Some(user.clone());
+
+        self.config.save_token(
+            &TokenKey::User(user.clone()),
+            &StoredToken::from_user(&token, expires_in),
+        );
+        self.insert_cell(user, token, expires_in);
+    }
+
+    /// Load the token saved for `user` in the token store of the client, returning if
+    /// there was one
+    pub fn restore<I: Into<UserId>>(&self, user: I) -> bool {
+        let user = user.into();
+
+        match self.config.load_token(&TokenKey::User(user.clone())) {
+            Some(stored) => {
+                self.insert_cell(user, stored.to_user(), stored.expires_in());
+                true
+            }
+            None => false,
+        }
+    }
+
+    fn insert_cell(&self, user: UserId, token: UserAuthToken, expires_in: Option<Duration>) {
+        let mut cell = TokenCell::new(Arc::new(token), expires_in);
+        if let Some(on_refresh) = &self.on_refresh {
+            let on_refresh = on_refresh.clone();
+            cell.set_on_refresh(Box::new(move |token| on_refresh(token)));
+        }
+
+        self.write().insert(user, Arc::new(cell));
+    }
+
+    /// Remove the token of `user` from the pool, returning it if there was one
+    pub fn remove<I: Into<UserId>>(&self, user: I) -> Option<Arc<UserAuthToken>> {
+        self.write().remove(&user.into()).map(|cell| cell.get())
+    }
+
+    /// Revoke the token of `user`, then remove it from the pool and the token store of the
+    /// client
+    pub async fn revoke<I: Into<UserId>>(&self, user: I) -> Result<(), PoolError> {
+        let user = user.into();
+        let token = self.cell(&user)?.get();
+
+        execute(&self.client, &self.config, &RevokeRequest::from(&*token))
+            .await
+            .map_err(|e| PoolError::RevokeFailed {
+                user: user.clone(),
+                from: e.into(),
+            })?;
+
+        self.write().remove(&user);
+        self.config.delete_token(&TokenKey::User(user));
+        Ok(())
+    }
+
+    /// If the pool has a token for `user`
+    pub fn contains<I: Into<UserId>>(&self, user: I) -> bool {
+        self.read().contains_key(&user.into())
+    }
+
+    /// The users that the pool has tokens for
+    pub fn users(&self) -> Vec<UserId> {
+        self.read().keys().cloned().collect()
+    }
+
+    /// The amount of tokens in the pool
+    pub fn len(&self) -> usize {
+        self.read().len()
+    }
+
+    /// If the pool has no tokens
+    pub fn is_empty(&self) -> bool {
+        self.read().is_empty()
+    }
+
+    /// Get the token of `user`, refreshing it first if it is about to expire
+    pub async fn get<I: Into<UserId>>(&self, user: I) -> Result<Arc<UserAuthToken>, PoolError> {
+        let user = user.into();
+        let cell = self.cell(&user)?;
+
+        if cell.expires_within(self.config.refresh_margin()) {
+            let (_, generation) = cell.snapshot();
+            self.refresh_cell(&user, &cell, generation).await?;
+        }
+
+        Ok(cell.get())
+    }
+
+    /// Get the token of `user` if its scopes meet `required`
+    pub async fn get_with_scopes<I, S>(
+        &self,
+        user: I,
+        required: S,
+    ) -> Result<Arc<UserAuthToken>, PoolError>
+    where
+        I: Into<UserId>,
+        S: Into<ScopeRequirement>,
+    {
+        let user = user.into();
+        let token = self.get(user.clone()).await?;
+        required
+            .into()
+            .check(token.scopes())
+            .map_err(|missing| PoolError::MissingScopes { user, missing })?;
+        Ok(token)
+    }
+
+    /// Get a new token for `user` now, instead of waiting for the current one to expire
+    pub async fn refresh<I: Into<UserId>>(&self, user: I) -> Result<(), PoolError> {
+        let user = user.into();
+        let cell = self.cell(&user)?;
+        let (_, generation) = cell.snapshot();
+        self.refresh_cell(&user, &cell, generation).await
+    }
+
+    /// Make `req` with the token of the broadcaster it is for, whose scopes must meet
+    /// `required`
+    ///
+    /// If twitch rejects the token it is refreshed and the request is sent once more.
+    pub async fn send<R, S>(
+        &self,
+        mut req: R,
+        required: S,
+    ) -> Result<R::Response, requests::RequestError<R::ErrorCodes>>
+    where
+        R: BroadcasterRequest<Headers = Arc<UserAuthToken>> + Sync,
+        S: Into<ScopeRequirement>,
+    {
+        let user: UserId = match req.broadcaster() {
+            Some(broadcaster) => broadcaster.clone().into(),
+            None => {
+                return Err(requests::RequestError::MalformedRequest(
+                    "Request has no broadcaster to pick a token for".into(),
+                ))
+            }
+        };
+        self.get_with_scopes(user.clone(), required).await?;
+
+        let cell = self.cell(&user)?;
+        let (token, generation) = cell.snapshot();
+        req.authorize(token);
+        req.ready()?;
+
+        let resp = send_with_retries(&self.client, &self.config, &req).await?;
+        if resp.status() != reqwest::StatusCode::UNAUTHORIZED {
+            return requests::parse_response::<R>(resp).await;
+        }
+
+        log::warn!(
+            "Token of user {:?} was rejected, refreshing it before sending the request again",
+            user
+        );
+        self.refresh_cell(&user, &cell, generation).await?;
+
+        req.authorize(cell.get());
+        execute(&self.client, &self.config, &req).await
+    }
+
+    fn cell(&self, user: &UserId) -> Result<Cell, PoolError> {
+        self.read()
+            .get(user)
+            .cloned()
+            .ok_or_else(|| PoolError::MissingToken(user.clone()))
+    }
+
+    async fn refresh_cell(
+        &self,
+        user: &UserId,
+        cell: &Cell,
+        generation: u64,
+    ) -> Result<(), PoolError> {
+        let (client, config, secret) = (&*self.client, &self.config, &self.secret);
+
+        cell.refresh(generation, |current| async move {
+            refresh_user_token(client, config, secret, &current).await
+        })
+        .await
+        .map_err(|from| PoolError::RefreshFailed {
+            user: user.clone(),
+            from,
+        })
+    }
+
+    fn read(&self) -> std::sync::RwLockReadGuard<'_, HashMap<UserId, Cell>> {
+        self.tokens.read().unwrap_or_else(|e| e.into_inner())
+    }
+
+    fn write(&self) -> std::sync::RwLockWriteGuard<'_, HashMap<UserId, Cell>> {
+        self.tokens.write().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+impl fmt::Debug for TokenPool {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("TokenPool")
+            .field("config", &self.config)
+            .field("users", &self.users())
+            .finish()
+    }
+}
@@ -0,0 +1,117 @@
+//! Storage for the token of an authorized [`Client`](super::Client) that is replaced
+//! when it expires or is rejected
+
+use std::fmt;
+use std::future::Future;
+use std::sync::RwLock;
+use std::time::{Duration, Instant};
+
+/// Called with the new token each time a [`Client`](super::Client) refreshes its token
+pub type RefreshCallback<K> = Box<dyn Fn(&K) + Send + Sync>;
+
+/// Holds the current token of a client state, and makes sure only one refresh happens
+/// at a time
+///
+/// Every refresh bumps the generation of the cell, so a task that saw a rejected token
+/// can tell if another task already replaced it while it waited for its turn.
+pub struct TokenCell<K> {
+    current: RwLock<Current<K>>,
+    refreshing: tokio::sync::Mutex<()>,
+    on_refresh: Option<RefreshCallback<K>>,
+}
+
+struct Current<K> {
+    token: K,
+    expires_at: Option<Instant>,
+    generation: u64,
+}
+
+impl<K: Clone> TokenCell<K> {
+    /// Store `token` that expires after `expires_in`, if known
+    pub(crate) fn new(token: K, expires_in: Option<Duration>) -> Self {
+        Self {
+            current: RwLock::new(Current {
+                token,
+                expires_at: expires_in.map(|e| Instant::now() + e),
+                generation: 0,
+            }),
+            refreshing: tokio::sync::Mutex::new(()),
+            on_refresh: None,
+        }
+    }
+
+    /// The current token
+    pub(crate) fn get(&self) -> K {
+        self.read(|c| c.token.clone())
+    }
+
+    /// The current token and the generation it belongs to
+    pub(crate) fn snapshot(&self) -> (K, u64) {
+        self.read(|c| (c.token.clone(), c.generation))
+    }
+
+    /// How long until the current token expires, if known
+    pub(crate) fn expires_in(&self) -> Option<Duration> {
+        self.read(|c| c.expires_at)
+            .map(|at| at.saturating_duration_since(Instant::now()))
+    }
+
+    /// If the current token expires within `margin`
+    pub(crate) fn expires_within(&self, margin: Duration) -> bool {
+        matches!(self.expires_in(), Some(left) if left <= margin)
+    }
+
+    /// Set the function called with every new token
+    pub(crate) fn set_on_refresh(&mut self, on_refresh: RefreshCallback<K>) {
+        self.on_refresh = Some(on_refresh);
+    }
+
+    /// Replace the token of `generation` with the one returned by `renew`
+    ///
+    /// If the token was already replaced by the time this task gets its turn, nothing
+    /// is requested and `Ok` is returned.
+    pub(crate) async fn refresh<F, Fut, E>(&self, generation: u64, renew: F) -> Result<(), E>
+    where
+        F: FnOnce(K) -> Fut,
+        Fut: Future<Output = Result<(K, Option<Duration>), E>>,
+    {
+        let _guard = self.refreshing.lock().await;
+
+        let (token, current_generation) = self.snapshot();
+        if current_generation != generation {
+            return Ok(());
+        }
+
+        let (token, expires_in) = renew(token).await?;
+
+        if let Some(on_refresh) = &self.on_refresh {
+            on_refresh(&token);
+        }
+
+        let mut current = self.current.write().unwrap_or_else(|e| e.into_inner());
+        *current = Current {
+            token,
+            expires_at: expires_in.map(|e| Instant::now() + e),
+            generation: generation + 1,
+        };
+
+        Ok(())
+    }
+
+    fn read<O>(&self, f: impl FnOnce(&Current<K>) -> O) -> O {
+        f(&self.current.read().unwrap_or_else(|e| e.into_inner()))
+    }
+}
+
+impl<K: fmt::Debug> fmt::Debug for TokenCell<K> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut s = f.debug_struct("TokenCell");
+        if let Ok(current) = self.current.read() {
+            s.field("token", &current.token)
+                .field("expires_at", &current.expires_at)
+                .field("generation", &current.generation);
+        }
+        s.field("on_refresh", &self.on_refresh.as_ref().map(|_| "Fn"))
+            .finish()
+    }
+}
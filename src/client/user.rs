@@ -0,0 +1,271 @@
+//! [`Client`] state for acting on behalf of a user
+
+use async_trait::async_trait;
+use reqwest::Client as RClient;
+use std::sync::Arc;
+use std::time::Duration;
+
+use super::refresh::TokenCell;
+use super::{execute, AuthorizedState, Client, ClientConfig, ClientState, Unauthorized, Users};
+use crate::api::ApiResult;
+use crate::auth::authorization_code::{CodeExchangeRequest, CodeExchangeResponse};
+use crate::auth::device_code::{poll_with, DeviceCodeRequest, DeviceCodeResponse};
+use crate::auth::refresh_token::RefreshTokenRequest;
+use crate::auth::scopes::ScopeSet;
+use crate::auth::store::{StoredToken, TokenKey};
+use crate::auth::user_token::UserAuthToken;
+use crate::auth::validate::ValidateRequest;
+use crate::auth::{AuthToken, ClientId, ClientSecret};
+use crate::requests::*;
+use crate::resource::users::get_users::UserDescription;
+use crate::values::users::{UserId, UserLogin};
+
+/// [`Client`] state holding a user access token, from exchanging an authorization code
+/// with [`Client::user_auth`] or polling for a device code with [`Client::device_auth`]
+#[derive(Debug)]
+pub struct UserToken {
+    token: TokenCell<Arc<UserAuthToken>>,
+    secret: ClientSecret,
+    user_id: UserId,
+    login: UserLogin,
+}
+
+impl ClientState for UserToken {}
+
+#[async_trait]
+impl AuthorizedState for UserToken {
+    type Token = Arc<UserAuthToken>;
+
+    fn cell(&self) -> &TokenCell<Self::Token> {
+        &self.token
+    }
+
+    fn cell_mut(&mut self) -> &mut TokenCell<Self::Token> {
+        &mut self.token
+    }
+
+    fn access_token(token: &Self::Token) -> &str {
+        &token.token
+    }
+
+    fn client_id(token: &Self::Token) -> &ClientId {
+        &token.client_id
+    }
+
+    fn token_key(&self) -> TokenKey {
+        TokenKey::User(self.user_id.clone())
+    }
+
+    async fn renew(
+        &self,
+        client: &RClient,
+        config: &ClientConfig,
+        current: Self::Token,
+    ) -> Result<(Self::Token, Option<Duration>), super::RequestError> {
+        refresh_user_token(client, config, &self.secret, &current).await
+    }
+}
+
+impl Client<Unauthorized> {
+    /// Exchange an authorization code, returned to `redirect_uri` after the user
+    /// authorized the application, for a user access token
+    ///
+    /// Returns self with the error if authorization failed so it may be tried again
+    pub async fn user_auth<C, R>(
+        self,
+        id: ClientId,
+        secret: ClientSecret,
+        code: C,
+        redirect_uri: R,
+    ) -> Result<Client<UserToken>, (super::RequestError, Self)>
+    where
+        C: Into<String>,
+        R: Into<String>,
+    {
+        let exchange = CodeExchangeRequest::from((id, secret.clone(), code, redirect_uri));
+
+        let resp = match self.execute(&exchange).await {
+            Ok(resp) => resp,
+            Err(e) => return Err((e.into(), self)),
+        };
+
+        self.authorize_user(secret, resp).await
+    }
+
+    /// Ask twitch for a device code, show the user [`DeviceCodeResponse::verification_uri`]
+    /// and then wait for them to authorize it with [`Client::device_auth`]
+    pub async fn device_code(
+        &self,
+        id: ClientId,
+        scopes: ScopeSet,
+    ) -> Result<DeviceCodeResponse, super::RequestError> {
+        Ok(self.execute(&DeviceCodeRequest::from((id, scopes))).await?)
+    }
+
+    /// Poll until the user authorized `device`, see [`crate::auth::device_code`]
+    ///
+    /// `scopes` must be the ones the device code was requested with, and `secret` is used
+    /// to refresh the token once it expires. Returns self with the error if the user did
+    /// not authorize the application before the code expired
+    pub async fn device_auth(
+        self,
+        id: ClientId,
+        secret: ClientSecret,
+        device: &DeviceCodeResponse,
+        scopes: ScopeSet,
+    ) -> Result<Client<UserToken>, (super::RequestError, Self)> {
+        let this = &self;
+        let polled = poll_with(
+            id,
+            device,
+            scopes,
+            |req| async move { this.execute(&req).await },
+        )
+        .await;
+
+        let resp = match polled {
+            Ok(resp) => resp,
+            Err(e) => return Err((e.into(), self)),
+        };
+
+        self.authorize_user(secret, resp).await
+    }
+
+    /// Find the user `resp` was issued for and move to acting on their behalf
+    async fn authorize_user(
+        self,
+        secret: ClientSecret,
+        resp: CodeExchangeResponse,
+    ) -> Result<Client<UserToken>, (super::RequestError, Self)> {
+        let validated = match self
+            .execute(&ValidateRequest::from(&*resp.access_token))
+            .await
+        {
+            Ok(validated) => validated,
+            Err(e) => return Err((e.into(), self)),
+        };
+
+        let (user_id, login) = match (validated.user_id.clone(), validated.login.clone()) {
+            (Some(user_id), Some(login)) => (user_id, login),
+            _ => {
+                return Err((
+                    RequestError::<CommonResponseCodes>::MissingAuth.into(),
+                    self,
+                ))
+            }
+        };
+
+        let expires_in = Some(Duration::from_secs(resp.expires_in.into()));
+        let token = validated.user_token(resp.access_token, Some(resp.refresh_token));
+
+        self.common.config.save_token(
+            &TokenKey::User(user_id.clone()),
+            &StoredToken::from_user(&token, expires_in),
+        );
+
+        Ok(Client {
+            common: self.common,
+            current: UserToken {
+                token: TokenCell::new(Arc::new(token), expires_in),
+                secret,
+                user_id,
+                login,
+            },
+        })
+    }
+}
+
+/// Get a new user token with the refresh token of `current`
+pub(super) async fn refresh_user_token(
+    client: &RClient,
+    config: &ClientConfig,
+    secret: &ClientSecret,
+    current: &UserAuthToken,
+) -> Result<(Arc<UserAuthToken>, Option<Duration>), super::RequestError> {
+    let req = match RefreshTokenRequest::from_token(current, secret.clone()) {
+        Some(req) => req,
+        None => return Err(RequestError::<CommonResponseCodes>::MissingAuth.into()),
+    };
+    let resp = execute(client, config, &req).await?;
+
+    let expires_in = resp
+        .expires_in
+        .map(|expires_in| Duration::from_secs(expires_in.into()));
+    let token = resp.into_token(current);
+
+    if let Some(user_id) = &token.user_id {
+        config.save_token(
+            &TokenKey::User(user_id.clone()),
+            &StoredToken::from_user(&token, expires_in),
+        );
+    }
+
+    Ok((Arc::new(token), expires_in))
+}
+
+impl Client<Unauthorized> {
+    /// Act for `user` with the token saved for them in the token store, see
+    /// [`ClientBuilder::token_store`](super::ClientBuilder::token_store)
+    ///
+    /// `secret` is used to refresh the token once it expires. Returns self with the error
+    /// if there is no saved token for the user
+    pub fn restore_user<I: Into<UserId>>(
+        self,
+        secret: ClientSecret,
+        user: I,
+    ) -> Result<Client<UserToken>, (super::RequestError, Self)> {
+        let user_id = user.into();
+
+        let stored = match self
+            .common
+            .config
+            .load_token(&TokenKey::User(user_id.clone()))
+        {
+            Some(stored) => stored,
+            None => {
+                return Err((
+                    RequestError::<CommonResponseCodes>::MissingAuth.into(),
+                    self,
+                ))
+            }
+        };
+
+        let mut token = stored.to_user();
+        token.user_id = Some(user_id.clone());
+        let login = token.login.clone().unwrap_or_default();
+
+        Ok(Client {
+            common: self.common,
+            current: UserToken {
+                token: TokenCell::new(Arc::new(token), stored.expires_in()),
+                secret,
+                user_id,
+                login,
+            },
+        })
+    }
+}
+
+impl Client<UserToken> {
+    /// The id of the user this client acts for
+    pub fn user_id(&self) -> &UserId {
+        &self.current.user_id
+    }
+
+    /// The login name of the user this client acts for
+    pub fn login(&self) -> &UserLogin {
+        &self.current.login
+    }
+
+    /// The scopes the user granted to this client
+    pub fn scopes(&self) -> ScopeSet {
+        self.current.token.get().scopes().clone()
+    }
+}
+
+impl<'c> Users<'c, UserToken> {
+    /// Get the user this client acts for
+    pub async fn me(&self) -> ApiResult<Option<UserDescription>> {
+        self.by_id(self.client.user_id().clone()).await
+    }
+}
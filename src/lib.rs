@@ -18,7 +18,7 @@
 //! 2) Request a client_flow Authentication Token
 //! 3) Use that token to request information on a user by their display name
 //! 4) Use the UserId returned by that request to request a list of clips associated
-//!     with their channel.
+//!    with their channel.
 //!
 //! ```
 //! # use std::env;
@@ -137,8 +137,9 @@
 #![deny(missing_docs, missing_debug_implementations)]
 #![cfg_attr(feature = "nightly", feature(doc_spotlight))]
 
+pub mod api;
 pub mod auth;
-mod client;
+pub mod client;
 pub mod requests;
 pub mod resource;
 pub mod values;
@@ -148,23 +149,25 @@ pub mod prelude {
     /// Trait used by many endpoints for authentication and scopes
     pub use crate::auth::AuthToken;
 
+    /// Kinds of tokens that endpoints are bound on
+    pub use crate::auth::{AppOrUserToken, AppToken, UserAccessToken};
+
     /// Trait that exposes methods common to all requests, required to use
     /// `.make_request(&client).await`
     pub use crate::requests::Request;
 
     /// Types produced and consumed by endpoints
     pub use crate::values;
+
+    /// Client that keeps its authorization for every request
+    pub use crate::client::Client;
 }
 
 mod crate_prelude {
-    pub use crate::auth::{self, AuthToken};
+    pub use crate::auth::AppOrUserToken;
     pub use crate::requests::*;
-    pub use reqwest::Method;
 
     pub mod serde_derive {
         pub use serde::{Deserialize, Serialize};
     }
-    pub mod serde_impl {
-        pub use serde::{ser::SerializeMap, Deserialize, Serialize, Serializer};
-    }
 }
@@ -1,7 +1,7 @@
 //! Common traits used to construct requestable types
 //!
 //! - TODO: Make `make_request` function based on feature and requests constructable without
-//!     async or sending in all cases
+//!   async or sending in all cases
 
 use async_trait::async_trait;
 use reqwest::Client;
@@ -31,7 +31,7 @@ impl Body for None {
     }
 }
 
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Deserialize)]
 /// Represents a sucessful request that was denied by the twitch api for some reason.
@@ -145,7 +145,7 @@ pub enum RequestError<C: ErrorCodes + 'static> {
 
     #[error("Unknown Error encountered {0:?}")]
     /// Unknown error
-    UnknownError(#[from] Box<dyn std::error::Error>),
+    UnknownError(#[from] Box<dyn std::error::Error + Send + Sync>),
 }
 
 /// Represents A Known set of error status codes that an endpoint may return.o
@@ -213,11 +213,11 @@ pub trait Headers {
 /// Must be able to borrow as a map of header names to values
 pub trait HeadersExt {
     /// Borrow the object as map from header names to values
-    fn as_ref<'a>(&'a self) -> &'a [(&'a str, &'a str)];
+    fn as_ref(&self) -> &[(&str, &str)];
 }
 
 impl<T: HeadersExt> Headers for T {
-    fn write_headers<'a>(&'a self, mut req: RequestBuilder) -> RequestBuilder {
+    fn write_headers(&self, mut req: RequestBuilder) -> RequestBuilder {
         for (a, b) in self.as_ref() {
             req = req.header(*a, *b);
         }
@@ -326,30 +326,154 @@ pub trait Request {
         // Make sure request thinks that it is ready to be sent
         self.ready()?;
 
-        // Build request with method and endpoint
-        let mut req = client.borrow().request(Self::METHOD, Self::ENDPOINT);
+        let client = client.borrow();
+        let req = build_request(self, client, Self::ENDPOINT).build()?;
 
-        // add headers, body, and params
-        req = self.headers().write_headers(req);
-        req = self.parameters().write_parameters(req);
-        req = self.body().write_body(req);
-
-        log::info!("Making request {:#?}", req);
+        // Plain requests have no client config, so only show them when debugging
+        let logging = crate::client::Logging {
+            level: Some(log::Level::Debug),
+            redact: true,
+        };
+        logging.log_request(&req);
 
         // send
-        let resp = req.send().await?;
+        let resp = client.execute(req).await?;
 
-        log::info!("Got response {:#?}", resp);
+        logging.log_response(&resp);
 
-        resp.json::<PossibleResponse<Self::Response>>()
-            .await?
-            .into_result()
-            .map_err(FailureStatus::into)
+        parse_response::<Self>(resp).await
     }
 }
 
+/// Build the request represented by `req` to be sent to `url`
+pub(crate) fn build_request<R>(req: &R, client: &Client, url: &str) -> RequestBuilder
+where
+    R: Request + ?Sized,
+{
+    // Build request with method and endpoint
+    let mut builder = client.request(R::METHOD, url);
+
+    // add headers, body, and params
+    builder = req.headers().write_headers(builder);
+    builder = req.parameters().write_parameters(builder);
+    req.body().write_body(builder)
+}
+
+/// Parse the response to a request of type `R`
+pub(crate) async fn parse_response<R>(
+    resp: reqwest::Response,
+) -> Result<R::Response, RequestError<R::ErrorCodes>>
+where
+    R: Request + ?Sized,
+{
+    // Some endpoints answer with an empty body on success
+    if resp.content_length() == Some(0) {
+        if let Ok(parsed) =
+            serde_json::from_value::<PossibleResponse<R::Response>>(serde_json::Value::Null)
+        {
+            return parsed.into_result().map_err(FailureStatus::into);
+        }
+    }
+
+    resp.json::<PossibleResponse<R::Response>>()
+        .await?
+        .into_result()
+        .map_err(FailureStatus::into)
+}
+
 /// Type that is returned by a sucessful request
 pub trait Response: DeserializeOwned + Sized {}
 
 // Auto impl for types that are already [`DeserializeOwned`]
 impl<T: DeserializeOwned> Response for T {}
+
+/// A [`Request`] that can be stored without its authorization and rebuilt later,
+/// for example to be pushed through a durable job queue.
+///
+/// ```
+/// # use twitch_api_rs::requests::*;
+/// # use twitch_api_rs::auth::client_credentials::ClientAuthToken;
+/// # use twitch_api_rs::resource::users::get_users::*;
+/// let job = GetUsersRequest::<ClientAuthToken>::builder()
+///     .add_login("TheHoodlum12")
+///     .to_job();
+///
+/// let stored = serde_json::to_string(&job).unwrap();
+///
+/// // Later, possibly in another process
+/// let job: GetUsersJob = serde_json::from_str(&stored).unwrap();
+/// let mut req = GetUsersRequest::from_job(job);
+/// # let token = ClientAuthToken::new(String::new(), String::new());
+/// req.set_auth(token);
+/// ```
+pub trait SerializableRequest: Request {
+    /// Everything needed to rebuild this request except for its authorization
+    type Job: Serialize + DeserializeOwned + Clone + std::fmt::Debug;
+
+    /// Get the job describing this request, does not include the authorization
+    fn to_job(&self) -> Self::Job;
+
+    /// Rebuild the request from a job, the authorization must be set again before
+    /// the request is ready to be sent
+    fn from_job(job: Self::Job) -> Self;
+}
+
+/// A [`Request`] made on behalf of a single broadcaster, so that the token of that
+/// broadcaster can be picked for it, see [`crate::client::TokenPool::send`]
+pub trait BroadcasterRequest: Request {
+    /// The broadcaster this request is made for, if it has been set
+    fn broadcaster(&self) -> Option<&crate::values::broadcasters::BroadcasterId>;
+
+    /// Set the token this request is sent with
+    fn authorize(&mut self, token: Self::Headers);
+}
+
+/// Error returned from [`ErasedRequest::make_erased_request`]
+///
+/// Can be downcast to the [`RequestError`] of the underlying request
+pub type ErasedRequestError = Box<dyn std::error::Error + Send + Sync>;
+
+/// Object safe version of [`Request`], so that requests for different endpoints can
+/// be stored together as `Box<dyn ErasedRequest>`.
+///
+/// Implemented for every [`Request`] whose response can be serialized.
+#[async_trait]
+pub trait ErasedRequest: std::fmt::Debug + Send + Sync {
+    /// Endpoint where the request is made, see [`Request::ENDPOINT`]
+    fn endpoint(&self) -> &'static str;
+
+    /// The method this request will use, see [`Request::METHOD`]
+    fn method(&self) -> reqwest::Method;
+
+    /// Make the request, returning the response as json
+    ///
+    /// Errors are the [`RequestError`] of the underlying request
+    async fn make_erased_request(
+        &self,
+        client: &Client,
+    ) -> Result<serde_json::Value, ErasedRequestError>;
+}
+
+#[async_trait]
+impl<R> ErasedRequest for R
+where
+    R: Request + std::fmt::Debug + Send + Sync,
+    R::Response: Serialize,
+    R::ErrorCodes: Send + Sync,
+{
+    fn endpoint(&self) -> &'static str {
+        R::ENDPOINT
+    }
+
+    fn method(&self) -> reqwest::Method {
+        R::METHOD
+    }
+
+    async fn make_erased_request(
+        &self,
+        client: &Client,
+    ) -> Result<serde_json::Value, ErasedRequestError> {
+        let resp = self.make_request(client).await?;
+        Ok(serde_json::to_value(resp)?)
+    }
+}
@@ -6,8 +6,10 @@
 pub mod get_channel_information {
 
     use crate::crate_prelude::{serde_derive::*, *};
+    use crate::response_object;
     use crate::values::broadcasters::*;
     use crate::values::games::*;
+    use crate::values::ExtraFields;
 
     #[derive(Debug, Clone, Serialize)]
     /// Request builder for the `Get Channel Information` endpoint
@@ -15,7 +17,7 @@ pub mod get_channel_information {
     /// See module level documentation for usage.
     pub struct GetChannelInformationRequest<A>
     where
-        A: AuthToken,
+        A: AppOrUserToken,
     {
         #[serde(skip)]
         auth: Option<A>,
@@ -25,7 +27,7 @@ pub mod get_channel_information {
 
     impl<A> Request for GetChannelInformationRequest<A>
     where
-        A: AuthToken + Sync,
+        A: AppOrUserToken + Sync,
     {
         const ENDPOINT: &'static str = "https://api.twitch.tv/helix/channels";
         const METHOD: reqwest::Method = reqwest::Method::GET;
@@ -69,7 +71,7 @@ pub mod get_channel_information {
 
     impl<A> GetChannelInformationRequest<A>
     where
-        A: AuthToken,
+        A: AppOrUserToken,
     {
         /// Set the authorization token for this request
         pub fn set_auth(&mut self, auth: A) -> &mut Self {
@@ -87,7 +89,48 @@ pub mod get_channel_information {
         }
     }
 
-    impl<A> ParametersExt for GetChannelInformationRequest<A> where A: AuthToken {}
+    impl<A> ParametersExt for GetChannelInformationRequest<A> where A: AppOrUserToken {}
+
+    impl<A> BroadcasterRequest for GetChannelInformationRequest<A>
+    where
+        A: AppOrUserToken + Sync,
+    {
+        fn broadcaster(&self) -> Option<&BroadcasterId> {
+            self.broadcaster_id.as_ref()
+        }
+
+        fn authorize(&mut self, token: A) {
+            self.auth.replace(token);
+        }
+    }
+
+    #[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+    /// A [`GetChannelInformationRequest`] without its authorization, see
+    /// [`SerializableRequest`]
+    pub struct GetChannelInformationJob {
+        /// The broadcaster whose channel information is requested
+        pub broadcaster_id: Option<BroadcasterId>,
+    }
+
+    impl<A> SerializableRequest for GetChannelInformationRequest<A>
+    where
+        A: AppOrUserToken + Sync,
+    {
+        type Job = GetChannelInformationJob;
+
+        fn to_job(&self) -> Self::Job {
+            GetChannelInformationJob {
+                broadcaster_id: self.broadcaster_id.clone(),
+            }
+        }
+
+        fn from_job(job: Self::Job) -> Self {
+            Self {
+                auth: None,
+                broadcaster_id: job.broadcaster_id,
+            }
+        }
+    }
 
     #[derive(Debug, Clone, Serialize, Deserialize)]
     #[allow(missing_docs)]
@@ -96,23 +139,35 @@ pub mod get_channel_information {
     pub struct GetChannelInformationResponse {
         #[serde(rename = "data")]
         pub channels: Vec<ChannelInformation>,
-    }
-
-    #[derive(Debug, Clone, Serialize, Deserialize)]
-    #[allow(missing_docs)]
-    /// Represents a single channel object
-    pub struct ChannelInformation {
-        pub broadcaster_id: BroadcasterId,
-        pub broadcaster_name: BroadcasterName,
 
-        /// The name of the game being played on the current stream
-        pub game_name: GameName,
-
-        /// The id of the game being played on the current stream
-        pub game_id: GameId,
-        pub broadcaster_language: BroadcasterLanguage,
+        /// Fields not yet known to this crate
+        #[serde(flatten)]
+        pub extra: ExtraFields,
+    }
 
-        /// The title of the current stream
-        pub title: String,
+    response_object! {
+        #[derive(Debug, Clone, Serialize, Deserialize)]
+        #[allow(missing_docs)]
+        /// Represents a single channel object
+        pub struct ChannelInformation {
+            #[no_default]
+            pub broadcaster_id: BroadcasterId,
+            pub broadcaster_name: BroadcasterName,
+
+            /// The name of the game being played on the current stream
+            pub game_name: GameName,
+
+            /// The id of the game being played on the current stream
+            pub game_id: GameId,
+            pub broadcaster_language: BroadcasterLanguage,
+
+            /// The title of the current stream
+            pub title: String,
+
+            /// Fields not yet known to this crate
+            #[serde(flatten)]
+            #[no_default]
+            pub extra: ExtraFields,
+        }
     }
 }
@@ -2,6 +2,7 @@
 
 use serde::{Deserialize, Serialize};
 
+use crate::response_object;
 use crate::values::*;
 use broadcasters::*;
 use clips::*;
@@ -9,25 +10,35 @@ use games::GameId;
 use users::*;
 use videos::VideoId;
 
-#[derive(Debug, Serialize, Deserialize)]
-#[allow(missing_docs)]
-/// Information relating to a single clip resource
-pub struct ClipInfo {
-    pub broadcaster_id: BroadcasterId,
-    pub broadcaster_name: BroadcasterName,
-    pub created_at: RFC3339Time,
-    pub creator_id: UserId,
-    pub creator_name: UserName,
-    pub embed_url: Url,
-    pub game_id: GameId,
-    #[serde(rename = "id")]
-    pub clip_id: ClipId,
-    pub language: ISOLanguage,
-    pub thumbnail_url: Url,
-    pub title: ClipTitle,
-    pub url: Url,
-    pub video_id: VideoId,
-    pub view_count: ViewCount,
+response_object! {
+    #[derive(Debug, Clone, Serialize, Deserialize)]
+    #[allow(missing_docs)]
+    /// Information relating to a single clip resource
+    pub struct ClipInfo {
+        #[no_default]
+        pub broadcaster_id: BroadcasterId,
+        pub broadcaster_name: BroadcasterName,
+        pub created_at: RFC3339Time,
+        #[no_default]
+        pub creator_id: UserId,
+        pub creator_name: UserName,
+        pub embed_url: Url,
+        pub game_id: GameId,
+        #[serde(rename = "id")]
+        #[no_default]
+        pub clip_id: ClipId,
+        pub language: ISOLanguage,
+        pub thumbnail_url: Url,
+        pub title: ClipTitle,
+        pub url: Url,
+        pub video_id: VideoId,
+        pub view_count: ViewCount,
+
+        /// Fields not yet known to this crate
+        #[serde(flatten)]
+        #[no_default]
+        pub extra: ExtraFields,
+    }
 }
 
 /// Request to the [`Get Clips`] endpoint
@@ -59,47 +70,46 @@ pub struct ClipInfo {
 pub mod get_clips {
     use super::ClipInfo;
     use super::*;
-    use crate::auth::AuthToken;
+    use crate::auth::AppOrUserToken;
     use crate::requests::*;
     use serde::{Deserialize, Serialize};
 
-    #[derive(Debug)]
-    enum PaginationDirection {
+    #[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+    /// Which way through the results a [`GetClipsRequest`] pages
+    pub enum PaginationDirection {
+        #[default]
+        /// Get the first page
         None,
+        /// Get the page before the cursor
         Before(Pagination),
+        /// Get the page after the cursor
         After(Pagination),
     }
 
-    impl Default for PaginationDirection {
-        fn default() -> Self {
-            Self::None
-        }
-    }
-
     use serde::ser::SerializeMap;
     use serde::Serializer;
 
-    #[derive(Debug)]
-    enum QueryType {
+    #[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+    /// What a [`GetClipsRequest`] searches by, only one may be used per request
+    pub enum QueryType {
+        #[default]
+        /// Not yet set, the request is not ready to be sent
         Unset,
+        /// Clips from a single broadcaster
         BroadCasterId(BroadcasterId),
+        /// Clips from a single game
         GameId(GameId),
+        /// Specific clips, at most 100
         ClipId(Vec<ClipId>),
     }
 
-    impl Default for QueryType {
-        fn default() -> Self {
-            Self::Unset
-        }
-    }
-
     #[derive(Debug)]
     /// Request builder for the [`Get Clips`] endpoint
     ///
     /// [`Get Clips`]: https://dev.twitch.tv/docs/api/reference#get-clips
     pub struct GetClipsRequest<A>
     where
-        A: AuthToken,
+        A: AppOrUserToken,
     {
         auth: Option<A>,
         query_type: QueryType,
@@ -110,7 +120,7 @@ pub mod get_clips {
 
     impl<A> Serialize for GetClipsRequest<A>
     where
-        A: AuthToken,
+        A: AppOrUserToken,
     {
         fn serialize<S>(&self, s: S) -> Result<S::Ok, S::Error>
         where
@@ -150,7 +160,7 @@ pub mod get_clips {
             }
 
             if let Some((start, maybe_end)) = &self.period {
-                map.serialize_entry("started_ad", start)?;
+                map.serialize_entry("started_at", start)?;
                 if let Some(end) = maybe_end.as_ref() {
                     map.serialize_entry("ended_at", end)?;
                 }
@@ -160,11 +170,11 @@ pub mod get_clips {
         }
     }
 
-    impl<A> ParametersExt for GetClipsRequest<A> where A: AuthToken {}
+    impl<A> ParametersExt for GetClipsRequest<A> where A: AppOrUserToken {}
 
     impl<A> Request for GetClipsRequest<A>
     where
-        A: AuthToken + Sync,
+        A: AppOrUserToken + Sync,
     {
         const ENDPOINT: &'static str = "https://api.twitch.tv/helix/clips";
         const METHOD: reqwest::Method = reqwest::Method::GET;
@@ -204,7 +214,7 @@ pub mod get_clips {
                     "Must provide at least one of broadcaster_id, game_id, clip_id".into(),
                 ))
             } else if let QueryType::ClipId(clips) = &self.query_type {
-                if clips.len() == 0 {
+                if clips.is_empty() {
                     Err(RequestError::MalformedRequest(
                         "Must provide at least one of broadcaster_id, game_id, clip_id".into(),
                     ))
@@ -213,17 +223,17 @@ pub mod get_clips {
                         "Cannot send more than 100 clip_ids at a time".into(),
                     ))
                 } else {
-                    Ok(())
+                    self.check_period()
                 }
             } else {
-                Ok(())
+                self.check_period()
             }
         }
     }
 
     impl<A> GetClipsRequest<A>
     where
-        A: AuthToken,
+        A: AppOrUserToken,
     {
         /// Set the authorization token to be used with this request
         pub fn set_auth(&mut self, auth: A) -> &mut Self {
@@ -294,35 +304,53 @@ pub mod get_clips {
 
         /// Set a time window filter, times are [`RFC3339`]
         ///
+        /// With the `chrono` or `time` features the times may also be datetimes from
+        /// those crates, and a window that ends before it starts is rejected when the
+        /// request is sent.
+        ///
+        /// # Panics
+        ///
+        /// With the `chrono` or `time` features, if either time is not [`RFC3339`]. Check
+        /// times from elsewhere with `RFC3339Time::parse` first.
+        ///
         /// [`RFC3339`]: https://datatracker.ietf.org/doc/rfc3339
+        #[track_caller]
         pub fn set_period<S, T>(&mut self, started_at: S, ended_at: T) -> &mut Self
         where
             S: Into<StartedAt>,
             T: Into<EndedAt>,
         {
-            self.period = Some((started_at.into(), Some(ended_at.into())));
+            self.period = Some((checked(started_at.into()), Some(checked(ended_at.into()))));
             self
         }
 
         /// Set the start of the date/time window filter, if set_ended_at not called then the
         /// window ends a week from this value
+        ///
+        /// Panics like [`Self::set_period`] on invalid times
+        #[track_caller]
         pub fn set_started_at<S>(&mut self, started_at: S) -> &mut Self
         where
             S: Into<StartedAt>,
         {
+            let started_at = checked(started_at.into());
             if let Some((start, _)) = &mut self.period {
-                *start = started_at.into();
+                *start = started_at;
             } else {
-                self.period = Some((started_at.into(), None));
+                self.period = Some((started_at, None));
             }
             self
         }
 
         /// Set the end of the date/time window filter, if `set_started_at` not called before this
         /// then it does nothing as and end may not be set without a start
+        ///
+        /// Panics like [`Self::set_period`] on invalid times
+        #[track_caller]
         pub fn set_ended_at<S: Into<EndedAt>>(&mut self, ended_at: S) -> &mut Self {
+            let ended_at = checked(ended_at.into());
             if let Some((_, end)) = &mut self.period {
-                end.replace(ended_at.into());
+                end.replace(ended_at);
             }
             self
         }
@@ -340,6 +368,109 @@ pub mod get_clips {
             self.pagination = PaginationDirection::After(after.into());
             self
         }
+
+        /// Set the time window filter from a [`Period`]
+        pub fn set_window<P: Into<Period>>(&mut self, period: P) -> &mut Self {
+            let Period {
+                started_at,
+                ended_at,
+            } = period.into();
+            self.period = Some((started_at, Some(ended_at)));
+            self
+        }
+
+        /// With the `chrono` or `time` features check that the time window is made of
+        /// valid times and does not end before it starts, times set on a [`GetClipsJob`]
+        /// have not been through the setters
+        fn check_period(&self) -> Result<(), RequestError<CommonResponseCodes>> {
+            #[cfg(any(feature = "chrono", feature = "time"))]
+            if let Some((start, end)) = &self.period {
+                let malformed = |e: InvalidTime| RequestError::MalformedRequest(e.to_string());
+                start.validate().map_err(malformed)?;
+
+                if let Some(end) = end {
+                    end.validate().map_err(malformed)?;
+                    if **end < **start {
+                        return Err(RequestError::MalformedRequest(
+                            "ended_at must not be before started_at".into(),
+                        ));
+                    }
+                }
+            }
+
+            Ok(())
+        }
+    }
+
+    /// With the `chrono` or `time` features panic if `time` is not a valid time
+    #[track_caller]
+    fn checked<T: std::ops::Deref<Target = RFC3339Time>>(time: T) -> T {
+        #[cfg(any(feature = "chrono", feature = "time"))]
+        if let Err(e) = time.validate() {
+            panic!("{}", e);
+        }
+        time
+    }
+
+    impl<A> BroadcasterRequest for GetClipsRequest<A>
+    where
+        A: AppOrUserToken + Sync,
+    {
+        fn broadcaster(&self) -> Option<&BroadcasterId> {
+            match &self.query_type {
+                QueryType::BroadCasterId(id) => Some(id),
+                _ => None,
+            }
+        }
+
+        fn authorize(&mut self, token: A) {
+            self.auth.replace(token);
+        }
+    }
+
+    #[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+    /// A [`GetClipsRequest`] without its authorization, see [`SerializableRequest`]
+    pub struct GetClipsJob {
+        /// What the request searches by
+        pub query_type: QueryType,
+
+        /// Pagination cursor and direction
+        #[serde(default)]
+        pub pagination: PaginationDirection,
+
+        /// Max amount of items to be returned
+        #[serde(default)]
+        pub count: Option<Count>,
+
+        /// Time window filter
+        #[serde(default)]
+        pub period: Option<(StartedAt, Option<EndedAt>)>,
+    }
+
+    impl<A> SerializableRequest for GetClipsRequest<A>
+    where
+        A: AppOrUserToken + Sync,
+    {
+        type Job = GetClipsJob;
+
+        fn to_job(&self) -> Self::Job {
+            GetClipsJob {
+                query_type: self.query_type.clone(),
+                pagination: self.pagination.clone(),
+                count: self.count,
+                period: self.period.clone(),
+            }
+        }
+
+        fn from_job(job: Self::Job) -> Self {
+            Self {
+                auth: None,
+                query_type: job.query_type,
+                pagination: job.pagination,
+                count: job.count,
+                period: job.period,
+            }
+        }
     }
 
     #[derive(Debug, Serialize, Deserialize)]
@@ -351,5 +482,9 @@ pub mod get_clips {
         pub clips: Vec<ClipInfo>,
         /// The inner may be empty, indicating that there was not more than the value passed to [`GetClipsRequest::set_count`]
         pub pagination: Pagination,
+
+        /// Fields not yet known to this crate
+        #[serde(flatten)]
+        pub extra: ExtraFields,
     }
 }
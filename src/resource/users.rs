@@ -23,7 +23,7 @@
 ///
 /// [`Get Users`]: https://dev.twitch.tv/docs/api/reference#get-users
 pub mod get_users {
-    use crate::auth::AuthToken;
+    use crate::auth::AppOrUserToken;
     use crate::requests::*;
     use serde::{
         ser::{SerializeMap, Serializer},
@@ -40,7 +40,7 @@ pub mod get_users {
     #[derive(Debug)]
     pub struct GetUsersRequest<A>
     where
-        A: AuthToken,
+        A: AppOrUserToken,
     {
         auth: Option<A>,
         id: Vec<UserId>,
@@ -49,7 +49,7 @@ pub mod get_users {
 
     impl<A> Request for GetUsersRequest<A>
     where
-        A: AuthToken + Send,
+        A: AppOrUserToken + Sync,
     {
         const ENDPOINT: &'static str = "https://api.twitch.tv/helix/users";
         const METHOD: reqwest::Method = reqwest::Method::GET;
@@ -85,7 +85,7 @@ pub mod get_users {
         fn ready(&self) -> Result<(), RequestError<Self::ErrorCodes>> {
             if self.auth.is_none() {
                 Err(RequestError::MissingAuth)
-            } else if self.id.len() == 0 && self.login.len() == 0 {
+            } else if self.id.is_empty() && self.login.is_empty() {
                 Err(RequestError::MalformedRequest(String::from(
                     "At least one id or login must be provided",
                 )))
@@ -99,7 +99,7 @@ pub mod get_users {
         }
     }
 
-    impl<A: AuthToken> GetUsersRequest<A> {
+    impl<A: AppOrUserToken> GetUsersRequest<A> {
         /// Set the authorization token to use
         ///
         /// Consumes the token, so should be cloned if you plan to re-use TODO offer reference
@@ -177,7 +177,7 @@ pub mod get_users {
     #[doc(hidden)]
     impl<A> Serialize for GetUsersRequest<A>
     where
-        A: AuthToken,
+        A: AppOrUserToken,
     {
         fn serialize<S>(&self, s: S) -> Result<S::Ok, S::Error>
         where
@@ -195,7 +195,41 @@ pub mod get_users {
     }
 
     #[doc(hidden)]
-    impl<A: AuthToken> ParametersExt for GetUsersRequest<A> {}
+    impl<A: AppOrUserToken> ParametersExt for GetUsersRequest<A> {}
+
+    #[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+    /// A [`GetUsersRequest`] without its authorization, see [`SerializableRequest`]
+    pub struct GetUsersJob {
+        /// The ids to search for
+        #[serde(default)]
+        pub ids: Vec<UserId>,
+
+        /// The logins to search for
+        #[serde(default)]
+        pub logins: Vec<UserName>,
+    }
+
+    impl<A> SerializableRequest for GetUsersRequest<A>
+    where
+        A: AppOrUserToken + Sync,
+    {
+        type Job = GetUsersJob;
+
+        fn to_job(&self) -> Self::Job {
+            GetUsersJob {
+                ids: self.id.clone(),
+                logins: self.login.clone(),
+            }
+        }
+
+        fn from_job(job: Self::Job) -> Self {
+            Self {
+                auth: None,
+                id: job.ids,
+                login: job.logins,
+            }
+        }
+    }
 
     /// A Collection of response items returned by [`GetUsersRequest`]
     #[derive(Debug, Serialize, Deserialize)]
@@ -203,33 +237,46 @@ pub mod get_users {
     pub struct GetUsersResponse {
         #[serde(rename = "data")]
         pub users: Vec<UserDescription>,
+
+        /// Fields not yet known to this crate
+        #[serde(flatten)]
+        pub extra: ExtraFields,
     }
 
+    use crate::response_object;
     use crate::values::broadcasters::*;
     use crate::values::users::UserLogin;
-    use crate::values::{RFC3339Time, Url};
+    use crate::values::{ExtraFields, RFC3339Time, Url};
 
-    #[derive(Debug, Serialize, Deserialize)]
-    #[allow(missing_docs)]
-    /// A single user datum returned by [`GetUsersRequest`]
-    pub struct UserDescription {
-        pub broadcaster_type: BroadcasterType,
-        pub description: String,
-        pub display_name: UserName,
-
-        /// The email of the user, will only be returned if the access token provided
-        /// has the [`scope`] 'user:read:email'
-        ///
-        /// [`scope`]: https://dev.twitch.tv/docs/authentication#scopes
-        pub email: Option<UserEmail>,
-        pub id: UserId,
-        pub login: UserLogin,
-        pub offline_image_url: Url,
-        pub profile_image_url: Url,
-
-        #[serde(rename = "type")]
-        user_type: UserType,
-        view_count: BroadcasterViews,
-        created_at: RFC3339Time,
+    response_object! {
+        #[derive(Debug, Clone, Serialize, Deserialize)]
+        #[allow(missing_docs)]
+        /// A single user datum returned by [`GetUsersRequest`]
+        pub struct UserDescription {
+            pub broadcaster_type: BroadcasterType,
+            pub description: String,
+            pub display_name: UserName,
+
+            /// The email of the user, will only be returned if the access token provided
+            /// has the [`scope`] 'user:read:email'
+            ///
+            /// [`scope`]: https://dev.twitch.tv/docs/authentication#scopes
+            pub email: Option<UserEmail>,
+            #[no_default]
+            pub id: UserId,
+            pub login: UserLogin,
+            pub offline_image_url: Url,
+            pub profile_image_url: Url,
+
+            #[serde(rename = "type")]
+            pub user_type: UserType,
+            pub view_count: BroadcasterViews,
+            pub created_at: RFC3339Time,
+
+            /// Fields not yet known to this crate
+            #[serde(flatten)]
+            #[no_default]
+            pub extra: ExtraFields,
+        }
     }
 }
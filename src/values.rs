@@ -59,16 +59,160 @@ macro_rules! from_inner {
     }
 }
 
+#[macro_export]
+#[doc(hidden)]
+/// Create an enum for a closed set of string values that twitch returns, with an
+/// `Unknown` variant that keeps values this crate does not know about yet
+///
+/// With the `strict` feature enabled deserializing an unknown value fails, so that
+/// additions to the api are caught in tests.
+macro_rules! string_enum {
+    (
+        $(#[$meta:meta])*
+        pub enum $name:ident {
+            $( $(#[$vmeta:meta])* $variant:ident => $value:literal, )+
+        }
+    ) => {
+        $(#[$meta])*
+        #[derive(Debug, Clone, PartialEq, Eq, Hash)]
+        pub enum $name {
+            $( $(#[$vmeta])* $variant, )+
+            /// A value that is not known to this crate, kept as twitch sent it
+            Unknown(String),
+        }
+
+        impl $name {
+            /// The value twitch uses for this variant
+            pub fn as_str(&self) -> &str {
+                match self {
+                    $( Self::$variant => $value, )+
+                    Self::Unknown(value) => value,
+                }
+            }
+        }
+
+        impl From<&str> for $name {
+            fn from(value: &str) -> Self {
+                match value {
+                    $( $value => Self::$variant, )+
+                    _ => Self::Unknown(value.into()),
+                }
+            }
+        }
+
+        impl From<String> for $name {
+            fn from(value: String) -> Self {
+                match value.as_str() {
+                    $( $value => Self::$variant, )+
+                    _ => Self::Unknown(value),
+                }
+            }
+        }
+
+        impl std::fmt::Display for $name {
+            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                f.write_str(self.as_str())
+            }
+        }
+
+        impl serde::Serialize for $name {
+            fn serialize<S>(&self, s: S) -> Result<S::Ok, S::Error>
+            where
+                S: serde::Serializer,
+            {
+                s.serialize_str(self.as_str())
+            }
+        }
+
+        impl<'de> serde::Deserialize<'de> for $name {
+            fn deserialize<D>(d: D) -> Result<Self, D::Error>
+            where
+                D: serde::Deserializer<'de>,
+            {
+                let value = Self::from(String::deserialize(d)?);
+
+                #[cfg(feature = "strict")]
+                if let Self::Unknown(value) = &value {
+                    return Err(serde::de::Error::custom(format!(
+                        "unknown {} {:?}",
+                        stringify!($name),
+                        value
+                    )));
+                }
+
+                Ok(value)
+            }
+        }
+    };
+}
+
+#[macro_export]
+#[doc(hidden)]
+/// Declare a response object whose fields fall back to their default when twitch leaves
+/// them out, unless they are marked `#[no_default]`
+///
+/// With the `strict` feature enabled every field must be sent, so that changes to the api
+/// are caught in tests.
+macro_rules! response_object {
+    (
+        $(#[$meta:meta])*
+        pub struct $name:ident { $($fields:tt)* }
+    ) => {
+        $crate::response_object!(@field [$(#[$meta])* pub struct $name] [] [] $($fields)*);
+    };
+
+    (@field $head:tt [$($done:tt)*] [$($attrs:tt)*] #[no_default] $($rest:tt)*) => {
+        $crate::response_object!(@required $head [$($done)*] [$($attrs)*] $($rest)*);
+    };
+    (@field $head:tt [$($done:tt)*] [$($attrs:tt)*] #[$m:meta] $($rest:tt)*) => {
+        $crate::response_object!(@field $head [$($done)*] [$($attrs)* #[$m]] $($rest)*);
+    };
+    (@field $head:tt [$($done:tt)*] [$($attrs:tt)*] pub $field:ident: $type:ty, $($rest:tt)*) => {
+        $crate::response_object!(
+            @field $head
+            [
+                $($done)*
+                $($attrs)*
+                #[cfg_attr(not(feature = "strict"), serde(default))]
+                pub $field: $type,
+            ]
+            []
+            $($rest)*
+        );
+    };
+
+    (@required $head:tt [$($done:tt)*] [$($attrs:tt)*] #[$m:meta] $($rest:tt)*) => {
+        $crate::response_object!(@required $head [$($done)*] [$($attrs)* #[$m]] $($rest)*);
+    };
+    (@required $head:tt [$($done:tt)*] [$($attrs:tt)*] pub $field:ident: $type:ty, $($rest:tt)*) => {
+        $crate::response_object!(
+            @field $head [$($done)* $($attrs)* pub $field: $type,] [] $($rest)*
+        );
+    };
+
+    (@field [$($head:tt)*] [$($done:tt)*] []) => {
+        $($head)* { $($done)* }
+    };
+}
+
 // }}}
 
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use thiserror::Error;
+
+#[cfg(any(feature = "chrono", feature = "time"))]
+use std::{
+    ops::Range,
+    time::{Duration, SystemTime},
+};
 
 /// Values for broadcaster objects and requests
 pub mod broadcasters {
     use super::*;
 
     #[repr(transparent)]
-    #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+    #[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
     #[serde(transparent)]
     /// The id number of a broadcaster object
     pub struct BroadcasterId(String);
@@ -81,25 +225,33 @@ pub mod broadcasters {
     }
 
     #[repr(transparent)]
-    #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+    #[derive(Debug, Clone, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
     #[serde(transparent)]
     /// The display name of a channel object
     pub struct BroadcasterName(String);
 
     #[repr(transparent)]
-    #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+    #[derive(Debug, Clone, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
     #[serde(transparent)]
     /// The language of a broadcaster object
     pub struct BroadcasterLanguage(ISOLanguage);
 
-    #[repr(transparent)]
-    #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
-    #[serde(transparent)]
-    /// The type of broadcaster: Affiliate, Partner, or empty
-    pub struct BroadcasterType(String);
+    string_enum! {
+        #[derive(Default)]
+        /// The type of broadcaster
+        pub enum BroadcasterType {
+            /// An affiliate broadcaster
+            Affiliate => "affiliate",
+            /// A partner broadcaster
+            Partner => "partner",
+            #[default]
+            /// A broadcaster that is neither an affiliate or partner, sent as an empty string
+            Normal => "",
+        }
+    }
 
     #[repr(transparent)]
-    #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+    #[derive(Debug, Clone, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
     #[serde(transparent)]
     /// The total number of views on this broadcasters channel
     pub struct BroadcasterViews(u64);
@@ -116,7 +268,6 @@ pub mod broadcasters {
         (BroadcasterId, String),
         (BroadcasterName, String),
         (BroadcasterLanguage, ISOLanguage),
-        (BroadcasterType, String),
         (BroadcasterViews, u64)
     ];
 
@@ -124,7 +275,6 @@ pub mod broadcasters {
         (BroadcasterId, String),
         (BroadcasterName, String),
         (BroadcasterLanguage, ISOLanguage),
-        (BroadcasterType, String),
         (BroadcasterViews, u64)
     ];
 }
@@ -134,14 +284,14 @@ pub mod games {
     use super::*;
 
     #[repr(transparent)]
-    #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+    #[derive(Debug, Clone, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
     #[serde(transparent)]
     pub struct GameName(String);
 
     #[repr(transparent)]
-    #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+    #[derive(Debug, Clone, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
     #[serde(transparent)]
-    /// The ID number of a game on twitch
+    /// The ID number of a game on twitch, empty for channels that have not set a game
     pub struct GameId(String);
 
     field_wrapper_name![
@@ -158,7 +308,7 @@ pub mod extensions {
     use super::*;
 
     #[repr(transparent)]
-    #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+    #[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
     #[serde(transparent)]
     /// The id assined to an extension when it was created
     pub struct ExtensionId(String);
@@ -176,17 +326,17 @@ pub mod clips {
     use super::*;
 
     #[repr(transparent)]
-    #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+    #[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
     #[serde(transparent)]
     pub struct ClipId(String);
 
     #[repr(transparent)]
-    #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+    #[derive(Debug, Clone, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
     #[serde(transparent)]
     pub struct ClipTitle(String);
 
     #[repr(transparent)]
-    #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+    #[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
     #[serde(transparent)]
     pub struct ViewCount(u32);
 
@@ -206,7 +356,7 @@ pub mod users {
     use super::*;
 
     #[repr(transparent)]
-    #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+    #[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
     #[serde(transparent)]
     pub struct UserId(String);
 
@@ -218,24 +368,34 @@ pub mod users {
     }
 
     #[repr(transparent)]
-    #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+    #[derive(Debug, Clone, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
     #[serde(transparent)]
     pub struct UserName(String);
 
     #[repr(transparent)]
-    #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+    #[derive(Debug, Clone, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
     #[serde(transparent)]
     /// the login name for this user
     pub struct UserLogin(String);
 
-    #[repr(transparent)]
-    #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
-    #[serde(transparent)]
-    /// The type of this user: Staff, Admin, Global_Mod, or empty
-    pub struct UserType(String);
+    string_enum! {
+        #[derive(Default)]
+        /// The type of this user
+        pub enum UserType {
+            /// A twitch staff member
+            Staff => "staff",
+            /// A twitch administrator
+            Admin => "admin",
+            /// A global moderator
+            GlobalMod => "global_mod",
+            #[default]
+            /// A user with no special type, sent as an empty string
+            Normal => "",
+        }
+    }
 
     #[repr(transparent)]
-    #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+    #[derive(Debug, Clone, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
     #[serde(transparent)]
     /// The email of the user, generally only returned by a request if the access token
     /// provided has the [`scope`] 'user:read:email'
@@ -255,7 +415,6 @@ pub mod users {
         (UserId, String),
         (UserName, String),
         (UserLogin, String),
-        (UserType, String),
         (UserEmail, String)
     ];
 
@@ -263,7 +422,6 @@ pub mod users {
         (UserId, String),
         (UserName, String),
         (UserLogin, String),
-        (UserType, String),
         (UserEmail, String)
     ];
 }
@@ -272,12 +430,13 @@ pub mod videos {
     use super::*;
 
     #[repr(transparent)]
-    #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+    #[derive(Debug, Clone, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
     #[serde(transparent)]
+    /// The id of a video, empty for clips whose video is not available
     pub struct VideoId(String);
 
     #[repr(transparent)]
-    #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+    #[derive(Debug, Clone, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
     #[serde(transparent)]
     pub struct VideoLanguage(ISOLanguage);
 
@@ -290,7 +449,7 @@ pub mod videos {
     from_inner![(VideoId, String), (VideoLanguage, ISOLanguage)];
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
 /// A Pagination key for enpoints that may return more than 100 results
 pub struct Pagination {
     pub cursor: Option<String>,
@@ -305,41 +464,318 @@ impl From<String> for Pagination {
 }
 
 #[repr(transparent)]
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(transparent)]
 /// The max amount returned per page, used in requests like
 /// [`crate::resource::clips::get_clips::GetClipsRequest`]
 pub struct Count(u32);
 
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
 /// Represents a time window
 pub struct Period {
     pub started_at: StartedAt,
     pub ended_at: EndedAt,
 }
 
-#[repr(transparent)]
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
-#[serde(transparent)]
+impl Period {
+    /// Create a time window from its start and end
+    pub fn new<S, E>(started_at: S, ended_at: E) -> Self
+    where
+        S: Into<StartedAt>,
+        E: Into<EndedAt>,
+    {
+        Self {
+            started_at: started_at.into(),
+            ended_at: ended_at.into(),
+        }
+    }
+
+    /// Create a time window that starts at `started_at` and lasts for `length`
+    #[cfg(any(feature = "chrono", feature = "time"))]
+    pub fn starting_at<T: Into<SystemTime>>(started_at: T, length: Duration) -> Self {
+        let started_at = started_at.into();
+        Self::new(started_at, started_at + length)
+    }
+
+    /// Create a time window that covers the last `length` of time, ending now
+    #[cfg(any(feature = "chrono", feature = "time"))]
+    pub fn last(length: Duration) -> Self {
+        let now = SystemTime::now();
+        Self::new(now - length, now)
+    }
+}
+
+/// Accepts ranges of [`SystemTime`], `chrono::DateTime` or `time::OffsetDateTime`
+#[cfg(any(feature = "chrono", feature = "time"))]
+impl<T: Into<SystemTime>> From<Range<T>> for Period {
+    fn from(range: Range<T>) -> Self {
+        Self::new(range.start.into(), range.end.into())
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
 /// Represents a [`RFC3339`] formatted datetime
 ///
+/// With the `chrono` or `time` features enabled the value is parsed once when it is
+/// created and checked when it is deserialized. It can then be converted to and from a
+/// typed datetime, and is ordered by the point in time that it represents.
+///
 /// [`RFC3339`]: https://datatracker.ietf.org/doc/rfc3339/
-pub struct RFC3339Time(String);
+pub struct RFC3339Time {
+    text: String,
+
+    /// The point in time `text` represents, `None` if it is not a valid time
+    #[cfg(any(feature = "chrono", feature = "time"))]
+    instant: Option<SystemTime>,
+}
 
 #[repr(transparent)]
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[cfg_attr(any(feature = "chrono", feature = "time"), derive(PartialOrd, Ord))]
 #[serde(transparent)]
 /// Represents the beginning of a time window
 pub struct StartedAt(RFC3339Time);
 
 #[repr(transparent)]
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[cfg_attr(any(feature = "chrono", feature = "time"), derive(PartialOrd, Ord))]
 #[serde(transparent)]
 /// Represents the end of a time window
 pub struct EndedAt(RFC3339Time);
 
+// {{{ timestamps
+
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+#[error("{0:?} is not a RFC3339 time")]
+/// A [`RFC3339Time`] that could not be parsed, contains the value that was rejected
+pub struct InvalidTime(pub String);
+
+impl RFC3339Time {
+    fn new(text: String) -> Self {
+        Self {
+            #[cfg(any(feature = "chrono", feature = "time"))]
+            instant: parse_system_time(&text),
+            text,
+        }
+    }
+
+    /// Parse a time, failing if it is not [`RFC3339`] formatted
+    ///
+    /// [`RFC3339`]: https://datatracker.ietf.org/doc/rfc3339/
+    #[cfg(any(feature = "chrono", feature = "time"))]
+    pub fn parse<S: Into<String>>(time: S) -> Result<Self, InvalidTime> {
+        let time = Self::new(time.into());
+        time.validate()?;
+        Ok(time)
+    }
+
+    /// Check that this value is [`RFC3339`] formatted, values that came from twitch
+    /// have already been checked
+    ///
+    /// [`RFC3339`]: https://datatracker.ietf.org/doc/rfc3339/
+    #[cfg(any(feature = "chrono", feature = "time"))]
+    pub fn validate(&self) -> Result<(), InvalidTime> {
+        self.to_system_time().map(|_| ())
+    }
+
+    /// Get the point in time that this value represents
+    #[cfg(any(feature = "chrono", feature = "time"))]
+    pub fn to_system_time(&self) -> Result<SystemTime, InvalidTime> {
+        self.instant.ok_or_else(|| InvalidTime(self.text.clone()))
+    }
+
+    /// Parse this value as a [`chrono::DateTime`] in utc
+    #[cfg(feature = "chrono")]
+    pub fn to_chrono(&self) -> Result<chrono::DateTime<chrono::Utc>, InvalidTime> {
+        self.to_system_time().map(Into::into)
+    }
+
+    /// Parse this value as a [`time::OffsetDateTime`], keeping its offset
+    #[cfg(feature = "time")]
+    pub fn to_offset_date_time(&self) -> Result<time::OffsetDateTime, InvalidTime> {
+        time::OffsetDateTime::parse(&self.text, &time::format_description::well_known::Rfc3339)
+            .map_err(|_| InvalidTime(self.text.clone()))
+    }
+
+    /// Take the text of this value
+    pub fn into_inner(self) -> String {
+        self.text
+    }
+}
+
+/// Read only, as changing the text would leave the parsed time behind
+impl std::ops::Deref for RFC3339Time {
+    type Target = String;
+    fn deref(&self) -> &String {
+        &self.text
+    }
+}
+
+impl Serialize for RFC3339Time {
+    fn serialize<S>(&self, ser: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        ser.serialize_str(&self.text)
+    }
+}
+
+impl<'de> Deserialize<'de> for RFC3339Time {
+    fn deserialize<D>(de: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        let time = Self::new(String::deserialize(de)?);
+
+        #[cfg(any(feature = "chrono", feature = "time"))]
+        time.validate().map_err(serde::de::Error::custom)?;
+
+        Ok(time)
+    }
+}
+
+/// Times that represent the same instant but are written differently are ordered by their
+/// text, so that the ordering agrees with [`PartialEq`]. Values that are not valid times
+/// are ordered before all valid ones.
+#[cfg(any(feature = "chrono", feature = "time"))]
+impl Ord for RFC3339Time {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        self.instant
+            .cmp(&other.instant)
+            .then_with(|| self.text.cmp(&other.text))
+    }
+}
+
+#[cfg(any(feature = "chrono", feature = "time"))]
+impl PartialOrd for RFC3339Time {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl From<String> for RFC3339Time {
+    fn from(time: String) -> Self {
+        Self::new(time)
+    }
+}
+
+impl From<&String> for RFC3339Time {
+    fn from(time: &String) -> Self {
+        Self::new(time.clone())
+    }
+}
+
+impl From<&str> for RFC3339Time {
+    fn from(time: &str) -> Self {
+        Self::new(time.into())
+    }
+}
+
+/// Formatted in utc
+///
+/// # Panics
+///
+/// If the year is outside of `0..=9999`, which can not be written as RFC3339
+#[cfg(any(feature = "chrono", feature = "time"))]
+impl From<SystemTime> for RFC3339Time {
+    fn from(time: SystemTime) -> Self {
+        Self {
+            text: format_system_time(time),
+            instant: Some(time),
+        }
+    }
+}
+
+/// Formatted in utc
+///
+/// # Panics
+///
+/// If the year is outside of `0..=9999`, which can not be written as RFC3339
+#[cfg(feature = "chrono")]
+impl<Tz: chrono::TimeZone> From<chrono::DateTime<Tz>> for RFC3339Time {
+    fn from(time: chrono::DateTime<Tz>) -> Self {
+        Self::from(SystemTime::from(time))
+    }
+}
+
+/// Formatted in utc
+///
+/// # Panics
+///
+/// If the year is outside of `0..=9999`, which can not be written as RFC3339
+#[cfg(feature = "time")]
+impl From<time::OffsetDateTime> for RFC3339Time {
+    fn from(time: time::OffsetDateTime) -> Self {
+        Self::from(SystemTime::from(time))
+    }
+}
+
+#[cfg(any(feature = "chrono", feature = "time"))]
+const OUT_OF_RANGE: &str = "Years outside of 0..=9999 cannot be written as RFC3339";
+
+#[cfg(feature = "time")]
+fn parse_system_time(time: &str) -> Option<SystemTime> {
+    time::OffsetDateTime::parse(time, &time::format_description::well_known::Rfc3339)
+        .ok()
+        .map(SystemTime::from)
+}
+
+/// Written like chrono does, with 3, 6 or 9 fractional digits when it is not a whole
+/// second, as `time` leaves out trailing zeros
+#[cfg(feature = "time")]
+fn format_system_time(time: SystemTime) -> String {
+    use std::convert::TryFrom;
+    use time::OffsetDateTime;
+
+    let time = match time.duration_since(std::time::UNIX_EPOCH) {
+        Ok(after) => time::Duration::try_from(after)
+            .ok()
+            .and_then(|after| OffsetDateTime::UNIX_EPOCH.checked_add(after)),
+        Err(e) => time::Duration::try_from(e.duration())
+            .ok()
+            .and_then(|before| OffsetDateTime::UNIX_EPOCH.checked_sub(before)),
+    };
+
+    let mut text = time
+        .and_then(|time| time.replace_nanosecond(0).ok())
+        .and_then(|whole| {
+            whole
+                .format(&time::format_description::well_known::Rfc3339)
+                .ok()
+        })
+        .expect(OUT_OF_RANGE);
+
+    let fraction = match time.map_or(0, |time| time.nanosecond()) {
+        0 => return text,
+        n if n % 1_000_000 == 0 => format!(".{:03}", n / 1_000_000),
+        n if n % 1_000 == 0 => format!(".{:06}", n / 1_000),
+        n => format!(".{:09}", n),
+    };
+
+    text.insert_str(text.len() - 1, &fraction);
+    text
+}
+
+#[cfg(all(feature = "chrono", not(feature = "time")))]
+fn parse_system_time(time: &str) -> Option<SystemTime> {
+    chrono::DateTime::parse_from_rfc3339(time)
+        .ok()
+        .map(SystemTime::from)
+}
+
+#[cfg(all(feature = "chrono", not(feature = "time")))]
+fn format_system_time(time: SystemTime) -> String {
+    use chrono::Datelike;
+
+    let time = chrono::DateTime::<chrono::Utc>::from(time);
+    assert!((0..=9999).contains(&time.year()), "{}", OUT_OF_RANGE);
+    time.to_rfc3339_opts(chrono::SecondsFormat::AutoSi, true)
+}
+
+// }}}
+
 #[repr(transparent)]
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
 #[serde(transparent)]
 /// Represents a language, either a [`ISO 639-1`] two-letter language code or 'other'
 ///
@@ -347,7 +783,7 @@ pub struct EndedAt(RFC3339Time);
 pub struct ISOLanguage(String);
 
 #[repr(transparent)]
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
 #[serde(transparent)]
 pub struct Url(String);
 
@@ -363,7 +799,6 @@ field_wrapper_name![
 ];
 
 quick_deref_into![
-    (RFC3339Time, String),
     (StartedAt, RFC3339Time),
     (EndedAt, RFC3339Time),
     (ISOLanguage, String),
@@ -371,13 +806,44 @@ quick_deref_into![
 ];
 
 from_inner![
-    (RFC3339Time, String),
     (StartedAt, RFC3339Time),
     (EndedAt, RFC3339Time),
     (ISOLanguage, String),
     (Url, String)
 ];
 
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+#[serde(transparent)]
+/// Fields of a response object that this crate does not know about yet, keyed by their
+/// name. Used with `#[serde(flatten)]` on response objects so that fields twitch adds
+/// are kept instead of silently dropped.
+///
+/// With the `strict` feature enabled deserializing fails if there are any unknown
+/// fields, so that changes to the api are caught in tests.
+pub struct ExtraFields(BTreeMap<String, serde_json::Value>);
+
+impl<'de> Deserialize<'de> for ExtraFields {
+    fn deserialize<D>(d: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        let fields = BTreeMap::<String, serde_json::Value>::deserialize(d)?;
+
+        #[cfg(feature = "strict")]
+        if !fields.is_empty() {
+            return Err(serde::de::Error::custom(format!(
+                "unknown fields {:?}",
+                fields.keys().collect::<Vec<_>>()
+            )));
+        }
+
+        Ok(Self(fields))
+    }
+}
+
+quick_deref_into![(ExtraFields, BTreeMap<String, serde_json::Value>)];
+from_inner![(ExtraFields, BTreeMap<String, serde_json::Value>)];
+
 /// Used to indicated that this type is used a field value
 pub trait FieldValue {
     /// Get the commonly used name of a field of this type that twitch is expecting
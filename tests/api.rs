@@ -0,0 +1,116 @@
+use twitch_api_rs::api::{mock::MockApi, ApiResult, TwitchApi};
+use twitch_api_rs::requests::RequestError;
+use twitch_api_rs::resource::channels::get_channel_information::*;
+use twitch_api_rs::resource::users::get_users::*;
+
+/// Example of business logic written against the facade
+async fn channel_title(api: &dyn TwitchApi, login: &str) -> ApiResult<Option<String>> {
+    let users = api
+        .get_users(GetUsersJob {
+            ids: vec![],
+            logins: vec![login.into()],
+        })
+        .await?
+        .users;
+
+    let user = match users.into_iter().next() {
+        Some(user) => user,
+        None => return Ok(None),
+    };
+
+    let channels = api
+        .get_channel_information(GetChannelInformationJob {
+            broadcaster_id: Some(user.id.into()),
+        })
+        .await?
+        .channels;
+
+    Ok(channels.into_iter().next().map(|c| c.title))
+}
+
+fn user(id: &str) -> UserDescription {
+    serde_json::from_value(serde_json::json!({
+        "id": id,
+        "login": "thehoodlum12",
+        "display_name": "TheHoodlum12",
+        "type": "",
+        "broadcaster_type": "",
+        "description": "",
+        "profile_image_url": "",
+        "offline_image_url": "",
+        "view_count": 0,
+        "created_at": "2019-11-18T00:47:34Z"
+    }))
+    .unwrap()
+}
+
+fn channel(id: &str, title: &str) -> ChannelInformation {
+    serde_json::from_value(serde_json::json!({
+        "broadcaster_id": id,
+        "broadcaster_name": "TheHoodlum12",
+        "broadcaster_language": "en",
+        "game_id": "",
+        "game_name": "",
+        "title": title
+    }))
+    .unwrap()
+}
+
+#[tokio::test]
+async fn mock_programmed_calls() {
+    let api = MockApi::new();
+
+    api.expect_get_users(
+        GetUsersJob {
+            ids: vec![],
+            logins: vec!["TheHoodlum12".into()],
+        },
+        Ok(GetUsersResponse {
+            users: vec![user("477906794")],
+            extra: Default::default(),
+        }),
+    )
+    .expect_get_channel_information(
+        GetChannelInformationJob {
+            broadcaster_id: Some("477906794".into()),
+        },
+        Ok(GetChannelInformationResponse {
+            channels: vec![channel("477906794", "Testing")],
+            extra: Default::default(),
+        }),
+    );
+
+    let title = channel_title(&api, "TheHoodlum12")
+        .await
+        .expect("Mock returned an error");
+
+    assert_eq!(title.as_deref(), Some("Testing"));
+    api.assert_done();
+}
+
+#[tokio::test]
+async fn mock_returns_errors() {
+    let api = MockApi::new();
+
+    api.expect_get_users(
+        GetUsersJob {
+            ids: vec![],
+            logins: vec!["TheHoodlum12".into()],
+        },
+        Err(RequestError::MissingAuth),
+    );
+
+    match channel_title(&api, "TheHoodlum12").await {
+        Err(RequestError::MissingAuth) => (),
+        other => panic!("Expected programmed error but got {:?}", other),
+    }
+
+    assert_eq!(api.remaining(), 0);
+}
+
+#[tokio::test]
+#[should_panic(expected = "unexpected call")]
+async fn mock_panics_on_unexpected_call() {
+    let api = MockApi::new();
+    let _ = channel_title(&api, "TheHoodlum12").await;
+}
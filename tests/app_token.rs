@@ -0,0 +1,139 @@
+mod mock_server;
+
+use mock_server::{Canned, MockServer};
+use std::time::Duration;
+use twitch_api_rs::auth::client_credentials::{ClientAuthResponse, ClientAuthToken};
+use twitch_api_rs::client::*;
+
+const SHORT_TOKEN: &str = r#"{"access_token":"app_token","expires_in":1,"token_type":"bearer"}"#;
+const NEW_APP_TOKEN: &str =
+    r#"{"access_token":"new_app_token","expires_in":5000,"token_type":"bearer"}"#;
+
+fn client(server: &MockServer) -> Client<Unauthorized> {
+    Client::builder()
+        .auth_base(format!("{}/oauth2", server.base))
+        .refresh_margin(Duration::from_millis(500))
+        .build()
+        .unwrap()
+}
+
+#[test]
+fn tokens_know_when_they_expire() {
+    let resp: ClientAuthResponse =
+        serde_json::from_str(r#"{"access_token":"app_token","expires_in":3600}"#).unwrap();
+    let token = ClientAuthToken::from_client(resp, "client_id");
+
+    assert!(!token.is_expired());
+    assert!(!token.expires_within(Duration::from_secs(60)));
+    assert!(token.expires_within(Duration::from_secs(3600)));
+    assert!(token.expires_at().unwrap() > token.issued_at());
+
+    let token = ClientAuthToken::new("app_token".to_string(), "client_id");
+    assert_eq!(token.expires_in(), None);
+    assert!(!token.expires_within(Duration::from_secs(u32::MAX.into())));
+
+    let token = token.with_expiry(Duration::ZERO);
+    assert!(token.is_expired());
+    assert_eq!(token.expires_in(), Some(Duration::ZERO));
+}
+
+#[tokio::test]
+async fn token_is_renewed_before_it_expires() {
+    let server = MockServer::start(vec![
+        Canned::json(200, SHORT_TOKEN),
+        Canned::json(200, NEW_APP_TOKEN),
+    ])
+    .await;
+
+    let tokens = client(&server)
+        .app_token_manager("client_id".into(), "client_secret".into())
+        .await
+        .expect("Could not get app token");
+    assert_eq!(tokens.token().token, "app_token");
+
+    let renewal = tokens.spawn_renewal();
+
+    let task_tokens = tokens.clone();
+    let seen = tokio::spawn(async move {
+        tokio::time::sleep(Duration::from_millis(900)).await;
+        task_tokens.token()
+    })
+    .await
+    .unwrap();
+
+    assert_eq!(seen.token, "new_app_token");
+    assert!(!seen.expires_within(Duration::from_secs(60)));
+    assert_eq!(tokens.token().token, "new_app_token");
+    assert_eq!(server.received().len(), 2);
+
+    renewal.abort();
+}
+
+#[tokio::test]
+async fn renewal_stops_when_manager_is_dropped() {
+    let server = MockServer::start(vec![Canned::json(200, SHORT_TOKEN)]).await;
+
+    let tokens = client(&server)
+        .app_token_manager("client_id".into(), "client_secret".into())
+        .await
+        .expect("Could not get app token");
+
+    let renewal = tokens.spawn_renewal();
+    drop(tokens);
+
+    tokio::time::timeout(Duration::from_secs(5), renewal)
+        .await
+        .expect("Renewal did not stop")
+        .unwrap();
+    assert_eq!(server.received().len(), 1);
+}
+
+#[tokio::test]
+async fn fresh_token_renews_expiring_token() {
+    let server = MockServer::start(vec![
+        Canned::json(200, SHORT_TOKEN),
+        Canned::json(200, NEW_APP_TOKEN),
+    ])
+    .await;
+
+    let tokens = Client::builder()
+        .auth_base(format!("{}/oauth2", server.base))
+        .build()
+        .unwrap()
+        .app_token_manager("client_id".into(), "client_secret".into())
+        .await
+        .expect("Could not get app token");
+
+    let token = tokens.fresh_token().await.expect("Could not renew token");
+    assert_eq!(token.token, "new_app_token");
+
+    let received = server.received();
+    assert!(received[1].request_line.starts_with("POST /oauth2/token?"));
+    assert!(received[1]
+        .request_line
+        .contains("client_secret=client_secret"));
+}
+
+#[tokio::test]
+async fn renewal_waits_for_tokens_shorter_than_the_margin() {
+    let server = MockServer::start(vec![
+        Canned::json(200, SHORT_TOKEN),
+        Canned::json(200, SHORT_TOKEN),
+        Canned::json(200, SHORT_TOKEN),
+    ])
+    .await;
+
+    let tokens = Client::builder()
+        .auth_base(format!("{}/oauth2", server.base))
+        .build()
+        .unwrap()
+        .app_token_manager("client_id".into(), "client_secret".into())
+        .await
+        .expect("Could not get app token");
+
+    let renewal = tokens.spawn_renewal();
+    tokio::time::sleep(Duration::from_millis(500)).await;
+
+    assert_eq!(server.received().len(), 2);
+    renewal.abort();
+}
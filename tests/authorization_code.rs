@@ -0,0 +1,132 @@
+use twitch_api_rs::auth::authorization_code::*;
+use twitch_api_rs::auth::scopes::{Scope, ScopeSet};
+use twitch_api_rs::auth::AuthToken;
+use twitch_api_rs::requests::{Request, RequestError};
+
+fn query(url: &str) -> Vec<(String, String)> {
+    url::Url::parse(url)
+        .unwrap()
+        .query_pairs()
+        .into_owned()
+        .collect()
+}
+
+fn param<'a>(params: &'a [(String, String)], name: &str) -> Option<&'a str> {
+    params
+        .iter()
+        .find(|(k, _)| k == name)
+        .map(|(_, v)| v.as_str())
+}
+
+#[test]
+fn authorize_url_has_all_parameters() {
+    let mut scopes = ScopeSet::new();
+    scopes.insert(Scope::UserReadEmail);
+    scopes.insert(Scope::ClipsEdit);
+
+    let authorize = AuthorizeUrl::new(
+        ResponseType::Code,
+        "client_id".to_string(),
+        "http://localhost/callback",
+    )
+    .scopes(scopes)
+    .force_verify(true);
+
+    let url = authorize.build();
+    assert!(url.starts_with("https://id.twitch.tv/oauth2/authorize?"));
+
+    let params = query(&url);
+    assert_eq!(param(&params, "response_type"), Some("code"));
+    assert_eq!(param(&params, "client_id"), Some("client_id"));
+    assert_eq!(
+        param(&params, "redirect_uri"),
+        Some("http://localhost/callback")
+    );
+    assert_eq!(param(&params, "force_verify"), Some("true"));
+    assert_eq!(
+        param(&params, "state"),
+        Some(authorize.csrf_state().secret())
+    );
+
+    let mut granted: Vec<&str> = param(&params, "scope").unwrap().split(' ').collect();
+    granted.sort_unstable();
+    assert_eq!(granted, vec!["clips:edit", "user:read:email"]);
+}
+
+#[test]
+fn states_are_random() {
+    let (a, b) = (CsrfState::random(), CsrfState::random());
+    assert_ne!(a, b);
+    assert_eq!(a.secret().len(), 32);
+}
+
+#[test]
+fn callback_is_checked_against_state() {
+    let state = CsrfState::new("expected");
+
+    let code = parse_callback(
+        "http://localhost/callback?code=abc&scope=user%3Aread%3Aemail+clips%3Aedit&state=expected",
+        &state,
+    )
+    .expect("Valid callback was rejected");
+    assert_eq!(code.code, "abc");
+    assert!(code.scopes.contains(Scope::UserReadEmail));
+    assert!(code.scopes.contains(Scope::ClipsEdit));
+
+    match parse_callback("http://localhost/callback?code=abc&state=other", &state) {
+        Err(CallbackError::StateMismatch) => (),
+        other => panic!("Expected a state mismatch but got {:?}", other),
+    }
+
+    match parse_callback("http://localhost/callback?code=abc", &state) {
+        Err(CallbackError::StateMismatch) => (),
+        other => panic!("Expected a state mismatch but got {:?}", other),
+    }
+
+    match parse_callback(
+        "http://localhost/callback?error=access_denied&error_description=The+user+denied+you+access&state=expected",
+        &state,
+    ) {
+        Err(CallbackError::Denied { error, description }) => {
+            assert_eq!(error, "access_denied");
+            assert_eq!(description, "The user denied you access");
+        }
+        other => panic!("Expected the user to have denied access but got {:?}", other),
+    }
+
+    match parse_callback("http://localhost/callback?state=expected", &state) {
+        Err(CallbackError::Missing("code")) => (),
+        other => panic!("Expected a missing code but got {:?}", other),
+    }
+}
+
+#[test]
+fn exchange_request_must_be_complete() {
+    let mut req = CodeExchangeRequest::builder();
+    req.set_client_id("client_id".to_string())
+        .set_client_secret("client_secret".to_string())
+        .set_code("abc");
+
+    match req.ready() {
+        Err(RequestError::MalformedRequest(msg)) => assert!(msg.contains("redirect_uri")),
+        other => panic!("Expected a malformed request but got {:?}", other),
+    }
+
+    req.set_redirect_uri("http://localhost/callback");
+    assert!(req.ready().is_ok());
+}
+
+#[test]
+fn exchange_response_becomes_user_token() {
+    let resp: CodeExchangeResponse = serde_json::from_str(
+        r#"{"access_token":"access","refresh_token":"refresh","expires_in":14124,"scope":["user:read:email"],"token_type":"bearer"}"#,
+    )
+    .unwrap();
+
+    assert_eq!(resp.expires_in, 14124);
+
+    let token = resp.into_token("client_id".to_string());
+    assert_eq!(token.token, "access");
+    assert_eq!(token.refresh_token.as_deref(), Some("refresh"));
+    assert!(token.scopes().contains(Scope::UserReadEmail));
+}
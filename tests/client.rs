@@ -0,0 +1,54 @@
+mod common;
+
+use twitch_api_rs::client::{Client, ClientCredentials};
+use twitch_api_rs::requests::Request;
+use twitch_api_rs::resource::users::get_users::GetUsersRequest;
+
+async fn authorized_client() -> Client<ClientCredentials> {
+    let (client_id, client_secret) = common::get_id_secret();
+
+    match Client::new((*common::client()).clone())
+        .client_auth(client_id.into(), client_secret.into())
+        .await
+    {
+        Ok(client) => client,
+        Err((e, _)) => panic!("Could not authorize client for reason {}", e),
+    }
+}
+
+#[tokio::test]
+async fn client_endpoints() {
+    common::init();
+    let client = authorized_client().await;
+
+    let user = match client.users().by_login("TheHoodlum12").await {
+        Ok(Some(user)) => user,
+        Ok(None) => panic!("Could not find user TheHoodlum12"),
+        Err(e) => panic!("Could not get user for reason {}", e),
+    };
+
+    // The client finds the same user as the request it wraps
+    let direct = GetUsersRequest::builder()
+        .set_auth(common::get_client_auth_token_correct().await)
+        .add_login("TheHoodlum12".to_string())
+        .make_request(common::client())
+        .await
+        .expect("Could not get user with the request");
+    assert_eq!(direct.users[0].id, user.id);
+
+    let channel = match client.channels().info(user.id.clone()).await {
+        Ok(channel) => channel,
+        Err(e) => panic!("Could not get channel information for reason {}", e),
+    };
+
+    let clips = match client.clips().for_broadcaster(user.id).await {
+        Ok(clips) => clips,
+        Err(e) => panic!("Could not get clips for reason {}", e),
+    };
+
+    log::info!(
+        "Got channel {:#?} with {} clips",
+        channel,
+        clips.clips.len()
+    );
+}
@@ -152,3 +152,17 @@ async fn bad_client_id() {
         ),
     }
 }
+
+#[test]
+fn client_credentials_send_scopes() {
+    use twitch_api_rs::auth::client_credentials::*;
+    use twitch_api_rs::requests::*;
+
+    let mut req = ClientAuthRequest::from(("client_id".to_string(), "secret".to_string()));
+    let params = serde_json::to_value(req.parameters()).unwrap();
+    assert!(params.get("scope").is_none());
+
+    req.set_scopes("chat:read clips:edit".parse().unwrap());
+    let params = serde_json::to_value(req.parameters()).unwrap();
+    assert_eq!(params["scope"], "clips:edit chat:read");
+}
@@ -0,0 +1,204 @@
+mod mock_server;
+
+use mock_server::{Canned, MockServer};
+use std::time::Duration;
+use twitch_api_rs::client::*;
+
+const TOKEN: &str = r#"{"access_token":"abc","expires_in":5000,"token_type":"bearer"}"#;
+const USERS: &str = r#"{"data":[{"id":"477906794","login":"thehoodlum12","display_name":"TheHoodlum12","type":"","broadcaster_type":"","description":"","profile_image_url":"","offline_image_url":"","view_count":0,"created_at":"2019-11-18T00:47:34Z"}]}"#;
+
+async fn client_for(server: &MockServer, retry: RetryPolicy) -> Client<ClientCredentials> {
+    let client = Client::builder()
+        .user_agent("twitch-api-rs-test")
+        .api_base(format!("{}/helix", server.base))
+        .auth_base(format!("{}/oauth2", server.base))
+        .retry_policy(retry)
+        .rate_limit(RateLimit::Wait {
+            max_wait: Duration::from_secs(2),
+        })
+        .default_header(
+            reqwest::header::HeaderName::from_static("x-test"),
+            reqwest::header::HeaderValue::from_static("yes"),
+        )
+        .build()
+        .expect("Could not build client");
+
+    match client
+        .client_auth("client_id".into(), "client_secret".into())
+        .await
+    {
+        Ok(client) => client,
+        Err((e, _)) => panic!("Could not authorize against mock server: {}", e),
+    }
+}
+
+#[tokio::test]
+async fn settings_apply_to_requests() {
+    let server = MockServer::start(vec![Canned::json(200, TOKEN), Canned::json(200, USERS)]).await;
+    let client = client_for(&server, RetryPolicy::none()).await;
+
+    let user = client
+        .users()
+        .by_login("TheHoodlum12")
+        .await
+        .expect("Request failed")
+        .expect("No user returned");
+
+    assert_eq!(&*user.id, "477906794");
+
+    let received = server.received();
+    assert_eq!(received.len(), 2);
+    assert!(received[0].request_line.starts_with("POST /oauth2/token?"));
+    assert!(received[1]
+        .request_line
+        .starts_with("GET /helix/users?login=TheHoodlum12"));
+    assert_eq!(received[1].header("user-agent"), Some("twitch-api-rs-test"));
+    assert_eq!(received[1].header("x-test"), Some("yes"));
+    assert_eq!(received[1].header("authorization"), Some("Bearer abc"));
+}
+
+#[tokio::test]
+async fn server_errors_are_retried() {
+    let server = MockServer::start(vec![
+        Canned::json(200, TOKEN),
+        Canned::json(503, r#"{"error":"Unavailable","status":503,"message":""}"#),
+        Canned::json(200, USERS),
+    ])
+    .await;
+
+    let client = client_for(
+        &server,
+        RetryPolicy {
+            max_retries: 1,
+            backoff: Duration::from_millis(10),
+        },
+    )
+    .await;
+
+    let users = client
+        .users()
+        .by_logins(vec!["TheHoodlum12"])
+        .await
+        .expect("Request was not retried");
+
+    assert_eq!(users.len(), 1);
+    assert_eq!(server.received().len(), 3);
+}
+
+#[tokio::test]
+async fn retries_are_limited() {
+    let error = r#"{"error":"Internal Server Error","status":500,"message":""}"#;
+    let server = MockServer::start(vec![
+        Canned::json(200, TOKEN),
+        Canned::json(500, error),
+        Canned::json(500, error),
+        Canned::json(200, USERS),
+    ])
+    .await;
+
+    let client = client_for(
+        &server,
+        RetryPolicy {
+            max_retries: 1,
+            backoff: Duration::from_millis(10),
+        },
+    )
+    .await;
+
+    match client.users().by_login("TheHoodlum12").await {
+        Err(twitch_api_rs::requests::RequestError::KnownErrorStatus(_)) => (),
+        other => panic!("Expected the server error but got {:?}", other),
+    }
+
+    assert_eq!(server.received().len(), 3);
+}
+
+#[tokio::test]
+async fn invalid_bodies_are_reqwest_errors() {
+    let server = MockServer::start(vec![
+        Canned::json(200, TOKEN),
+        Canned::json(200, "not json"),
+        Canned::json(200, ""),
+    ])
+    .await;
+    let client = client_for(&server, RetryPolicy::none()).await;
+
+    for _ in 0..2 {
+        match client.users().by_login("TheHoodlum12").await {
+            Err(twitch_api_rs::requests::RequestError::ReqwestError(e)) => assert!(e.is_decode()),
+            other => panic!("Expected a decode error but got {:?}", other),
+        }
+    }
+}
+
+#[tokio::test]
+async fn posts_are_not_retried() {
+    let server = MockServer::start(vec![
+        Canned::json(503, r#"{"error":"Unavailable","status":503,"message":""}"#),
+        Canned::json(200, TOKEN),
+    ])
+    .await;
+
+    let client = Client::builder()
+        .auth_base(format!("{}/oauth2", server.base))
+        .retry_policy(RetryPolicy {
+            max_retries: 1,
+            backoff: Duration::from_millis(10),
+        })
+        .build()
+        .expect("Could not build client");
+
+    assert!(client
+        .client_auth("client_id".into(), "client_secret".into())
+        .await
+        .is_err());
+    assert_eq!(server.received().len(), 1);
+}
+
+#[tokio::test]
+async fn rate_limits_are_waited_on() {
+    let reset = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap()
+        .as_secs()
+        + 1;
+
+    let server = MockServer::start(vec![
+        Canned::json(200, TOKEN),
+        Canned::json(
+            429,
+            r#"{"error":"Too Many Requests","status":429,"message":""}"#,
+        )
+        .header("Ratelimit-Reset", &reset.to_string()),
+        Canned::json(200, USERS),
+    ])
+    .await;
+
+    let client = client_for(&server, RetryPolicy::none()).await;
+
+    let user = client
+        .users()
+        .by_login("TheHoodlum12")
+        .await
+        .expect("Rate limited request was not sent again");
+
+    assert!(user.is_some());
+    assert_eq!(server.received().len(), 3);
+}
+
+#[test]
+fn urls_are_rewritten() {
+    let client = Client::builder()
+        .api_base("http://localhost:8080/mock/")
+        .build()
+        .unwrap();
+
+    assert_eq!(
+        client.config().url_for("https://api.twitch.tv/helix/users"),
+        "http://localhost:8080/mock/users"
+    );
+    assert_eq!(
+        client.config().url_for("https://id.twitch.tv/oauth2/token"),
+        "https://id.twitch.tv/oauth2/token"
+    );
+}
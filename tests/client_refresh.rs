@@ -0,0 +1,203 @@
+mod mock_server;
+
+use mock_server::{Canned, MockServer};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use twitch_api_rs::client::*;
+
+const USERS: &str = r#"{"data":[{"id":"477906794","login":"thehoodlum12","display_name":"TheHoodlum12","type":"","broadcaster_type":"","description":"","profile_image_url":"","offline_image_url":"","view_count":0,"created_at":"2019-11-18T00:47:34Z"}]}"#;
+const UNAUTHORIZED: &str =
+    r#"{"error":"Unauthorized","status":401,"message":"Invalid OAuth token"}"#;
+
+fn app_token(token: &str, expires_in: u32) -> Canned {
+    Canned::json(
+        200,
+        &format!(
+            r#"{{"access_token":"{}","expires_in":{},"token_type":"bearer"}}"#,
+            token, expires_in
+        ),
+    )
+}
+
+async fn app_client(server: &MockServer) -> Client<ClientCredentials> {
+    let client = Client::builder()
+        .api_base(format!("{}/helix", server.base))
+        .auth_base(format!("{}/oauth2", server.base))
+        .retry_policy(RetryPolicy::none())
+        .build()
+        .unwrap();
+
+    match client
+        .client_auth("client_id".into(), "client_secret".into())
+        .await
+    {
+        Ok(client) => client,
+        Err((e, _)) => panic!("Could not authorize against mock server: {}", e),
+    }
+}
+
+#[tokio::test]
+async fn rejected_token_is_refreshed_and_request_replayed() {
+    let server = MockServer::start(vec![
+        app_token("first", 5000),
+        Canned::json(401, UNAUTHORIZED),
+        app_token("second", 5000),
+        Canned::json(200, USERS),
+    ])
+    .await;
+
+    let refreshed = Arc::new(AtomicUsize::new(0));
+    let counter = refreshed.clone();
+    let client = app_client(&server).await.on_token_refresh(move |token| {
+        assert_eq!(token.token, "second");
+        counter.fetch_add(1, Ordering::SeqCst);
+    });
+
+    let user = client
+        .users()
+        .by_login("TheHoodlum12")
+        .await
+        .expect("Request was not replayed");
+
+    assert!(user.is_some());
+    assert_eq!(client.token().token, "second");
+    assert_eq!(refreshed.load(Ordering::SeqCst), 1);
+
+    let received = server.received();
+    assert_eq!(received.len(), 4);
+    assert_eq!(received[1].header("authorization"), Some("Bearer first"));
+    assert!(received[2].request_line.starts_with("POST /oauth2/token?"));
+    assert_eq!(received[3].header("authorization"), Some("Bearer second"));
+}
+
+#[tokio::test]
+async fn rejected_twice_returns_error() {
+    let server = MockServer::start(vec![
+        app_token("first", 5000),
+        Canned::json(401, UNAUTHORIZED),
+        app_token("second", 5000),
+        Canned::json(401, UNAUTHORIZED),
+    ])
+    .await;
+
+    let client = app_client(&server).await;
+
+    match client.users().by_login("TheHoodlum12").await {
+        Err(twitch_api_rs::requests::RequestError::KnownErrorStatus(_)) => (),
+        other => panic!("Expected the authorization error but got {:?}", other),
+    }
+
+    assert_eq!(server.received().len(), 4);
+}
+
+#[tokio::test]
+async fn expiring_token_is_refreshed_first() {
+    let server = MockServer::start(vec![
+        app_token("first", 30),
+        app_token("second", 5000),
+        Canned::json(200, USERS),
+    ])
+    .await;
+
+    let client = app_client(&server).await;
+    assert!(client.token_expires_in().unwrap() <= Duration::from_secs(30));
+
+    client
+        .users()
+        .by_login("TheHoodlum12")
+        .await
+        .expect("Request failed");
+
+    let received = server.received();
+    assert_eq!(received.len(), 3);
+    assert!(received[1].request_line.starts_with("POST /oauth2/token?"));
+    assert_eq!(received[2].header("authorization"), Some("Bearer second"));
+}
+
+#[tokio::test]
+async fn concurrent_refreshes_are_deduplicated() {
+    let mut responses = vec![app_token("first", 30), app_token("second", 5000)];
+    responses.extend((0..5).map(|_| Canned::json(200, USERS)));
+    let server = MockServer::start(responses).await;
+
+    let client = app_client(&server).await;
+    let users = client.users();
+
+    let results = tokio::join!(
+        users.by_login("a"),
+        users.by_login("b"),
+        users.by_login("c"),
+        users.by_login("d"),
+        users.by_login("e"),
+    );
+
+    for result in [results.0, results.1, results.2, results.3, results.4] {
+        result.expect("Request failed");
+    }
+
+    let received = server.received();
+    let token_requests = received
+        .iter()
+        .filter(|r| r.request_line.starts_with("POST /oauth2/token?"))
+        .count();
+
+    assert_eq!(token_requests, 2);
+    assert_eq!(received.len(), 7);
+}
+
+#[tokio::test]
+async fn user_token_is_refreshed_with_refresh_token() {
+    let server = MockServer::start(vec![
+        Canned::json(
+            200,
+            r#"{"access_token":"first","refresh_token":"refresh_one","expires_in":5000,"scope":[],"token_type":"bearer"}"#,
+        ),
+        Canned::json(
+            200,
+            r#"{"client_id":"client_id","login":"thehoodlum12","user_id":"477906794","scopes":[],"expires_in":5000}"#,
+        ),
+        Canned::json(401, UNAUTHORIZED),
+        Canned::json(
+            200,
+            r#"{"access_token":"second","refresh_token":"refresh_two","expires_in":5000,"scope":[],"token_type":"bearer"}"#,
+        ),
+        Canned::json(200, USERS),
+    ])
+    .await;
+
+    let client = Client::builder()
+        .api_base(format!("{}/helix", server.base))
+        .auth_base(format!("{}/oauth2", server.base))
+        .build()
+        .unwrap();
+
+    let client = match client
+        .user_auth(
+            "client_id".into(),
+            "client_secret".into(),
+            "code",
+            "http://localhost/callback",
+        )
+        .await
+    {
+        Ok(client) => client,
+        Err((e, _)) => panic!("Could not exchange code: {}", e),
+    };
+
+    client.users().me().await.expect("Request was not replayed");
+
+    let token = client.token();
+    assert_eq!(token.token, "second");
+    assert_eq!(token.refresh_token.as_deref(), Some("refresh_two"));
+    assert_eq!(&**client.user_id(), "477906794");
+
+    let received = server.received();
+    assert!(received[3]
+        .request_line
+        .contains("grant_type=refresh_token"));
+    assert!(received[3]
+        .request_line
+        .contains("refresh_token=refresh_one"));
+    assert_eq!(received[4].header("authorization"), Some("Bearer second"));
+}
@@ -0,0 +1,114 @@
+mod mock_server;
+
+use mock_server::{Canned, MockServer};
+use twitch_api_rs::auth::scopes::Scope;
+use twitch_api_rs::client::*;
+
+const CODE_TOKEN: &str = r#"{
+    "access_token": "user_token",
+    "refresh_token": "refresh",
+    "expires_in": 14124,
+    "scope": ["user:read:email", "clips:edit"],
+    "token_type": "bearer"
+}"#;
+
+const VALIDATE: &str = r#"{
+    "client_id": "client_id",
+    "login": "thehoodlum12",
+    "scopes": ["user:read:email", "clips:edit"],
+    "user_id": "477906794",
+    "expires_in": 14124
+}"#;
+
+const USERS: &str = r#"{"data":[{"id":"477906794","login":"thehoodlum12","display_name":"TheHoodlum12","type":"","broadcaster_type":"","description":"","profile_image_url":"","offline_image_url":"","view_count":0,"created_at":"2019-11-18T00:47:34Z","email":"a@b.c"}]}"#;
+
+#[tokio::test]
+async fn authorization_code_exchange() {
+    let server = MockServer::start(vec![
+        Canned::json(200, CODE_TOKEN),
+        Canned::json(200, VALIDATE),
+        Canned::json(200, USERS),
+    ])
+    .await;
+
+    let client = Client::builder()
+        .api_base(format!("{}/helix", server.base))
+        .auth_base(format!("{}/oauth2", server.base))
+        .build()
+        .unwrap();
+
+    let client = match client
+        .user_auth(
+            "client_id".into(),
+            "client_secret".into(),
+            "the_code",
+            "http://localhost/callback",
+        )
+        .await
+    {
+        Ok(client) => client,
+        Err((e, _)) => panic!("Could not exchange code: {}", e),
+    };
+
+    assert_eq!(&**client.user_id(), "477906794");
+    assert_eq!(&**client.login(), "thehoodlum12");
+    assert!(client.scopes().contains(Scope::UserReadEmail));
+    assert!(client.scopes().contains(Scope::ClipsEdit));
+    assert_eq!(client.token().refresh_token.as_deref(), Some("refresh"));
+
+    let me = client
+        .users()
+        .me()
+        .await
+        .expect("Could not get user")
+        .expect("No user returned");
+    assert_eq!(me.email.as_deref().map(|e| e.as_str()), Some("a@b.c"));
+
+    let received = server.received();
+    assert!(received[0].request_line.contains("code=the_code"));
+    assert!(received[0]
+        .request_line
+        .contains("grant_type=authorization_code"));
+    assert_eq!(
+        received[1].header("authorization"),
+        Some("OAuth user_token")
+    );
+    assert!(received[2].request_line.contains("id=477906794"));
+    assert_eq!(
+        received[2].header("authorization"),
+        Some("Bearer user_token")
+    );
+}
+
+#[tokio::test]
+async fn failed_exchange_returns_client() {
+    let server = MockServer::start(vec![Canned::json(
+        400,
+        r#"{"status":400,"message":"Invalid authorization code"}"#,
+    )])
+    .await;
+
+    let client = Client::builder()
+        .auth_base(format!("{}/oauth2", server.base))
+        .build()
+        .unwrap();
+
+    match client
+        .user_auth(
+            "client_id".into(),
+            "client_secret".into(),
+            "bad_code",
+            "http://localhost/callback",
+        )
+        .await
+    {
+        Ok(_) => panic!("Exchange should have failed"),
+        Err((e, client)) => {
+            eprintln!("Failed correctly with {}", e);
+            assert_eq!(
+                client.config().auth_base(),
+                format!("{}/oauth2", server.base)
+            );
+        }
+    }
+}
@@ -1,19 +1,14 @@
 use reqwest::Client;
-use std::sync::atomic::AtomicBool;
 use std::sync::Arc;
 use twitch_api_rs::auth::client_credentials::ClientAuthToken;
 use twitch_api_rs::auth::client_credentials::*;
 use twitch_api_rs::requests::*;
 
 use lazy_static::lazy_static;
-use tokio::runtime::Builder;
 
 lazy_static! {
     static ref CLIENT_SHARED: Arc<Client> = Arc::new(Client::new());
-    static ref GUARD: () = {
-        let _ = env_logger::builder().is_test(true).init();
-        ()
-    };
+    static ref GUARD: () = env_logger::builder().is_test(true).init();
 }
 
 pub async fn get_client_auth_token_correct() -> Arc<ClientAuthToken> {
@@ -24,7 +19,6 @@ pub async fn get_client_auth_token_correct() -> Arc<ClientAuthToken> {
         .set_client_secret(client_secret)
         .make_request(client())
         .await
-        .ok()
         .expect("Did not get a sucessful response from the server");
 
     Arc::new(ClientAuthToken::from_client(resp, client_id))
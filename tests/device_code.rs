@@ -0,0 +1,159 @@
+mod mock_server;
+
+use mock_server::{Canned, MockServer};
+use twitch_api_rs::auth::device_code::*;
+use twitch_api_rs::auth::scopes::{Scope, ScopeSet};
+use twitch_api_rs::client::*;
+use twitch_api_rs::requests::{ErrorCodes, FailureStatus, Request, RequestError};
+
+const DEVICE: &str = r#"{
+    "device_code": "device",
+    "expires_in": 1800,
+    "interval": 0,
+    "user_code": "ABCDEFGH",
+    "verification_uri": "https://www.twitch.tv/activate?public=true&device-code=ABCDEFGH"
+}"#;
+
+const PENDING: &str = r#"{"status":400,"message":"authorization_pending"}"#;
+
+const TOKEN: &str = r#"{
+    "access_token": "user_token",
+    "refresh_token": "refresh",
+    "expires_in": 14124,
+    "scope": ["chat:read"],
+    "token_type": "bearer"
+}"#;
+
+const VALIDATE: &str = r#"{
+    "client_id": "client_id",
+    "login": "thehoodlum12",
+    "scopes": ["chat:read"],
+    "user_id": "477906794",
+    "expires_in": 14124
+}"#;
+
+fn scopes() -> ScopeSet {
+    let mut scopes = ScopeSet::new();
+    scopes.insert(Scope::ChatRead);
+    scopes
+}
+
+fn client(server: &MockServer) -> Client<Unauthorized> {
+    Client::builder()
+        .auth_base(format!("{}/oauth2", server.base))
+        .build()
+        .unwrap()
+}
+
+#[test]
+fn errors_are_matched_by_message() {
+    let status = |message: &str| FailureStatus {
+        error: None,
+        status: 400,
+        message: message.to_string(),
+    };
+
+    let known =
+        |message: &str| DeviceTokenErrorCodes::from_status(status(message)).map(|s| s.status);
+
+    assert_eq!(
+        known("authorization_pending").unwrap(),
+        DeviceTokenErrorCodes::AuthorizationPending
+    );
+    assert_eq!(known("slow_down").unwrap(), DeviceTokenErrorCodes::SlowDown);
+    assert_eq!(
+        known("expired_token").unwrap(),
+        DeviceTokenErrorCodes::ExpiredToken
+    );
+    assert!(known("something else").is_err());
+}
+
+#[test]
+fn token_request_must_be_complete() {
+    let mut req = DeviceTokenRequest::builder();
+    req.set_client_id("client_id".to_string());
+
+    match req.ready() {
+        Err(RequestError::MalformedRequest(msg)) => assert!(msg.contains("device_code")),
+        other => panic!("Expected a malformed request but got {:?}", other),
+    }
+
+    req.set_device_code("device");
+    assert!(req.ready().is_ok());
+}
+
+#[tokio::test]
+async fn polls_until_authorized() {
+    let server = MockServer::start(vec![
+        Canned::json(200, DEVICE),
+        Canned::json(400, PENDING),
+        Canned::json(400, PENDING),
+        Canned::json(200, TOKEN),
+        Canned::json(200, VALIDATE),
+    ])
+    .await;
+    let client = client(&server);
+
+    let device = client
+        .device_code("client_id".into(), scopes())
+        .await
+        .expect("Could not get device code");
+    assert_eq!(device.user_code, "ABCDEFGH");
+
+    let client = match client
+        .device_auth(
+            "client_id".into(),
+            "client_secret".into(),
+            &device,
+            scopes(),
+        )
+        .await
+    {
+        Ok(client) => client,
+        Err((e, _)) => panic!("Could not authorize device: {}", e),
+    };
+
+    assert_eq!(&**client.login(), "thehoodlum12");
+    assert!(client.scopes().contains(Scope::ChatRead));
+    assert_eq!(client.token().refresh_token.as_deref(), Some("refresh"));
+
+    let received = server.received();
+    assert_eq!(received.len(), 5);
+    assert!(received[0].request_line.contains("/oauth2/device?"));
+    assert!(received[0].request_line.contains("scopes=chat%3Aread"));
+    assert!(received[1].request_line.contains("device_code=device"));
+    assert!(received[1]
+        .request_line
+        .contains("grant_type=urn%3Aietf%3Aparams%3Aoauth%3Agrant-type%3Adevice_code"));
+}
+
+#[tokio::test]
+async fn expired_code_stops_polling() {
+    let server = MockServer::start(vec![
+        Canned::json(400, PENDING),
+        Canned::json(400, r#"{"status":400,"message":"expired_token"}"#),
+    ])
+    .await;
+    let device: DeviceCodeResponse = serde_json::from_str(DEVICE).unwrap();
+
+    let err = match client(&server)
+        .device_auth(
+            "client_id".into(),
+            "client_secret".into(),
+            &device,
+            scopes(),
+        )
+        .await
+    {
+        Ok(_) => panic!("Authorized with an expired code"),
+        Err((e, _)) => e,
+    };
+
+    match err.kind() {
+        RequestErrorType::DeviceAuthError {
+            from: RequestError::KnownErrorStatus(failure),
+        } => assert_eq!(failure.status, DeviceTokenErrorCodes::ExpiredToken),
+        other => panic!("Expected an expired token but got {:?}", other),
+    }
+    assert_eq!(server.received().len(), 2);
+}
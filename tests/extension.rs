@@ -0,0 +1,141 @@
+#![cfg(feature = "extensions")]
+
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use twitch_api_rs::auth::extension::*;
+use twitch_api_rs::requests::Headers;
+
+/// base64 of `extension secret for tests`
+const SECRET: &str = "ZXh0ZW5zaW9uIHNlY3JldCBmb3IgdGVzdHM=";
+const OTHER_SECRET: &str = "b3RoZXIgc2VjcmV0";
+
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap()
+        .as_secs()
+}
+
+fn token() -> ExtensionToken {
+    ExtensionToken::new("client_id", SECRET, "477906794")
+        .unwrap()
+        .channel_id("477906794")
+        .send(PubsubTarget::Broadcast)
+        .listen(PubsubTarget::Whisper("UG12X345T6J78".into()))
+}
+
+#[test]
+fn token_is_signed_with_secret() {
+    let token = token();
+    let claims = ExtensionVerifier::new(SECRET)
+        .unwrap()
+        .verify(&token.jwt())
+        .expect("Token was not signed with the secret");
+
+    assert_eq!(claims.role, ExtensionRole::External);
+    assert_eq!(
+        claims.user_id.as_deref().map(String::as_str),
+        Some("477906794")
+    );
+    assert_eq!(claims.pubsub_perms.send, vec![PubsubTarget::Broadcast]);
+    assert_eq!(
+        claims.pubsub_perms.listen,
+        vec![PubsubTarget::Whisper("UG12X345T6J78".into())]
+    );
+    assert!(claims.exp > now() && claims.exp <= now() + DEFAULT_LIFETIME.as_secs());
+
+    assert_eq!(
+        serde_json::to_value(&claims).unwrap(),
+        serde_json::json!({
+            "exp": claims.exp,
+            "role": "external",
+            "user_id": "477906794",
+            "channel_id": "477906794",
+            "pubsub_perms": { "listen": ["whisper-UG12X345T6J78"], "send": ["broadcast"] },
+        })
+    );
+
+    assert!(ExtensionVerifier::new(OTHER_SECRET)
+        .unwrap()
+        .verify(&token.jwt())
+        .is_err());
+    assert!(matches!(
+        ExtensionToken::new("client_id", "not base64!", "477906794"),
+        Err(ExtensionError::Secret(_))
+    ));
+}
+
+#[test]
+fn token_is_sent_as_bearer() {
+    let token = token();
+    let req = token
+        .write_headers(reqwest::Client::new().get("http://localhost"))
+        .build()
+        .unwrap();
+
+    assert_eq!(
+        req.headers()["authorization"],
+        format!("Bearer {}", token.jwt())
+    );
+    assert_eq!(req.headers()["client-id"], "client_id");
+}
+
+#[test]
+fn token_is_signed_again_when_it_expires() {
+    let token = token().lifetime(Duration::from_secs(60));
+    assert_eq!(token.jwt(), token.jwt());
+
+    let token = token.lifetime(MIN_LIFETIME);
+    let first = token.jwt();
+    std::thread::sleep(Duration::from_millis(1100));
+
+    let second = token.clone().jwt();
+    assert_ne!(first, second);
+    assert_eq!(token.jwt(), second);
+}
+
+#[test]
+fn short_lifetimes_are_raised() {
+    let token = token().lifetime(Duration::from_secs(1));
+    let jwt = token.jwt();
+    assert_eq!(token.jwt(), jwt);
+
+    let claims = ExtensionVerifier::new(SECRET)
+        .unwrap()
+        .verify(&jwt)
+        .unwrap();
+    assert!(claims.exp >= now() + MIN_LIFETIME.as_secs() - 1);
+}
+
+#[test]
+fn frontend_tokens_are_verified() {
+    let verifier = ExtensionVerifier::new(SECRET).unwrap();
+    let key = jsonwebtoken::EncodingKey::from_base64_secret(SECRET).unwrap();
+    let sign = |claims: serde_json::Value| {
+        jsonwebtoken::encode(&jsonwebtoken::Header::default(), &claims, &key).unwrap()
+    };
+
+    let claims = verifier
+        .verify(&sign(serde_json::json!({
+            "exp": now() + 60,
+            "opaque_user_id": "UG12X345T6J78",
+            "channel_id": "477906794",
+            "role": "viewer",
+            "is_unlinked": true,
+            "pubsub_perms": { "listen": ["broadcast", "global"] },
+        })))
+        .expect("Valid frontend token");
+    assert_eq!(claims.role, ExtensionRole::Viewer);
+    assert_eq!(claims.opaque_user_id.as_deref(), Some("UG12X345T6J78"));
+    assert_eq!(claims.user_id, None);
+    assert_eq!(claims.is_unlinked, Some(true));
+    assert_eq!(
+        claims.pubsub_perms.listen,
+        vec![PubsubTarget::Broadcast, PubsubTarget::Global]
+    );
+
+    let expired = sign(serde_json::json!({ "exp": now() - 120, "role": "viewer" }));
+    assert!(matches!(
+        verifier.verify(&expired),
+        Err(ExtensionError::Jwt(_))
+    ));
+}
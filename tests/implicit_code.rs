@@ -0,0 +1,83 @@
+use twitch_api_rs::auth::implicit_code::*;
+use twitch_api_rs::auth::scopes::{Scope, ScopeSet};
+use twitch_api_rs::auth::AuthToken;
+
+#[test]
+fn authorize_url_asks_for_token() {
+    let mut scopes = ScopeSet::new();
+    scopes.insert(Scope::ChatRead);
+
+    let authorize = AuthorizeUrl::new(
+        ResponseType::Token,
+        "client_id".to_string(),
+        "http://localhost/callback",
+    )
+    .scopes(scopes);
+    let url = url::Url::parse(&authorize.build()).unwrap();
+    let params: Vec<(String, String)> = url.query_pairs().into_owned().collect();
+
+    assert!(params.contains(&("response_type".into(), "token".into())));
+    assert!(params.contains(&("scope".into(), "chat:read".into())));
+    assert!(params.contains(&("force_verify".into(), "false".into())));
+    assert!(params.contains(&("state".into(), authorize.csrf_state().secret().to_string())));
+}
+
+#[test]
+fn token_is_read_from_fragment() {
+    let authorize = AuthorizeUrl::new(
+        ResponseType::Token,
+        "client_id".to_string(),
+        "http://localhost/callback",
+    )
+    .state(CsrfState::new("c3ab8aa609ea11e793ae92361f002671"));
+
+    let token = authorize
+        .parse_redirect(
+            "http://localhost/callback#access_token=0123456789abcdefghijABCDEFGHIJ&scope=chat%3Aread+whispers%3Aread&state=c3ab8aa609ea11e793ae92361f002671&token_type=bearer",
+        )
+        .expect("Valid redirect was rejected");
+
+    assert_eq!(token.token, "0123456789abcdefghijABCDEFGHIJ");
+    assert_eq!(token.refresh_token, None);
+    assert_eq!(&*token.client_id, "client_id");
+    assert!(token.scopes().contains(Scope::ChatRead));
+    assert!(token.scopes().contains(Scope::WhispersRead));
+    assert!(!token.scopes().contains(Scope::ChannelModerate));
+}
+
+#[test]
+fn bad_redirects_are_rejected() {
+    let state = CsrfState::new("expected");
+
+    match parse_redirect(
+        "http://localhost/callback#access_token=abc&state=forged",
+        &state,
+        "client_id".to_string(),
+    ) {
+        Err(CallbackError::StateMismatch) => (),
+        other => panic!("Expected a state mismatch but got {:?}", other),
+    }
+
+    match parse_redirect(
+        "http://localhost/callback?error=access_denied&error_description=The+user+denied+you+access&state=expected",
+        &state,
+        "client_id".to_string(),
+    ) {
+        Err(CallbackError::Denied { error, .. }) => assert_eq!(error, "access_denied"),
+        other => panic!("Expected the user to have denied access but got {:?}", other),
+    }
+
+    match parse_redirect(
+        "http://localhost/callback#state=expected",
+        &state,
+        "client_id".to_string(),
+    ) {
+        Err(CallbackError::Missing("access_token")) => (),
+        other => panic!("Expected a missing token but got {:?}", other),
+    }
+
+    match parse_redirect("not a url", &state, "client_id".to_string()) {
+        Err(CallbackError::InvalidUrl(_)) => (),
+        other => panic!("Expected an invalid url but got {:?}", other),
+    }
+}
@@ -0,0 +1,103 @@
+use std::sync::Arc;
+use twitch_api_rs::auth::client_credentials::ClientAuthToken;
+use twitch_api_rs::requests::*;
+
+type Token = Arc<ClientAuthToken>;
+
+fn token() -> Token {
+    Arc::new(ClientAuthToken::new(
+        "not_a_token".to_string(),
+        "not_a_client".to_string(),
+    ))
+}
+
+#[test]
+fn clips_job_round_trip() {
+    use twitch_api_rs::resource::clips::get_clips::*;
+
+    let job = GetClipsRequest::<Token>::builder()
+        .add_clip_id("AwkwardHelplessSalamanderSwiftRage")
+        .add_clip_id("KnottyOddFishShazBotstix")
+        .to_job();
+
+    let stored = serde_json::to_string(&job).expect("Could not serialize job");
+    let restored: GetClipsJob = serde_json::from_str(&stored).expect("Could not deserialize job");
+
+    assert_eq!(job, restored, "Job did not round trip");
+
+    let mut req = GetClipsRequest::<Token>::from_job(restored);
+    assert!(
+        matches!(req.ready(), Err(RequestError::MissingAuth)),
+        "Request from job should not carry any auth"
+    );
+
+    req.set_auth(token());
+    assert!(req.ready().is_ok(), "Request from job was not ready");
+}
+
+#[test]
+fn users_job_round_trip() {
+    use twitch_api_rs::resource::users::get_users::*;
+
+    let job = GetUsersRequest::<Token>::builder()
+        .add_id("477906794")
+        .add_login("TheHoodlum12")
+        .to_job();
+
+    let stored = serde_json::to_value(&job).expect("Could not serialize job");
+    let restored: GetUsersJob = serde_json::from_value(stored).expect("Could not deserialize job");
+
+    assert_eq!(job, restored, "Job did not round trip");
+
+    let mut req = GetUsersRequest::<Token>::from_job(restored);
+    req.set_auth(token());
+    assert!(req.ready().is_ok(), "Request from job was not ready");
+}
+
+#[test]
+fn erased_requests() {
+    use twitch_api_rs::resource::channels::get_channel_information::*;
+    use twitch_api_rs::resource::users::get_users::*;
+
+    let queue: Vec<Box<dyn ErasedRequest>> = vec![
+        Box::new(GetUsersRequest::<Token>::from_job(GetUsersJob {
+            ids: vec![],
+            logins: vec!["TheHoodlum12".into()],
+        })),
+        Box::new(GetChannelInformationRequest::<Token>::from_job(
+            GetChannelInformationJob {
+                broadcaster_id: Some("477906794".into()),
+            },
+        )),
+    ];
+
+    let endpoints: Vec<&str> = queue.iter().map(|r| r.endpoint()).collect();
+
+    assert_eq!(
+        endpoints,
+        vec![
+            "https://api.twitch.tv/helix/users",
+            "https://api.twitch.tv/helix/channels"
+        ]
+    );
+}
+
+#[tokio::test]
+async fn erased_request_keeps_error() {
+    use twitch_api_rs::resource::users::get_users::*;
+
+    let req: Box<dyn ErasedRequest> = Box::new(GetUsersRequest::<Token>::from_job(GetUsersJob {
+        ids: vec!["477906794".into()],
+        logins: vec![],
+    }));
+
+    let error = req
+        .make_erased_request(&reqwest::Client::new())
+        .await
+        .expect_err("Request without auth should not be sent");
+
+    match error.downcast_ref::<RequestError<CommonResponseCodes>>() {
+        Some(RequestError::MissingAuth) => (),
+        other => panic!("Expected MissingAuth but got {:?}", other),
+    }
+}
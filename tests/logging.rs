@@ -0,0 +1,80 @@
+mod mock_server;
+
+use mock_server::{Canned, MockServer};
+use std::sync::Mutex;
+use twitch_api_rs::client::*;
+
+const TOKEN: &str = r#"{"access_token":"abc","expires_in":5000,"token_type":"bearer"}"#;
+const SECRET: &str = "SUPERSECRET";
+
+/// Keeps every line logged by this crate
+struct Capture(Mutex<Vec<(log::Level, String)>>);
+
+impl log::Log for Capture {
+    fn enabled(&self, _: &log::Metadata) -> bool {
+        true
+    }
+
+    fn log(&self, record: &log::Record) {
+        if record.target().starts_with("twitch_api_rs") {
+            self.0
+                .lock()
+                .unwrap()
+                .push((record.level(), record.args().to_string()));
+        }
+    }
+
+    fn flush(&self) {}
+}
+
+static CAPTURE: Capture = Capture(Mutex::new(Vec::new()));
+
+fn lines_about(server: &MockServer) -> Vec<(log::Level, String)> {
+    CAPTURE
+        .0
+        .lock()
+        .unwrap()
+        .iter()
+        .filter(|(_, line)| line.contains(&server.base))
+        .cloned()
+        .collect()
+}
+
+fn assert_redacted(lines: &[(log::Level, String)]) {
+    assert!(
+        lines.iter().any(|(_, l)| l.starts_with("Making request")),
+        "Request was not logged: {:?}",
+        lines
+    );
+    assert!(
+        lines.iter().any(|(_, l)| l.starts_with("Got response")),
+        "Response was not logged: {:?}",
+        lines
+    );
+
+    for (_, line) in lines {
+        assert!(!line.contains(SECRET), "Secret was logged: {}", line);
+        assert!(line.contains("client_secret=%3Credacted%3E"), "{}", line);
+    }
+}
+
+#[tokio::test]
+async fn secrets_are_not_logged() {
+    let _ = log::set_logger(&CAPTURE);
+    log::set_max_level(log::LevelFilter::Trace);
+
+    let server = MockServer::start(vec![Canned::json(200, TOKEN)]).await;
+
+    let client = Client::builder()
+        .auth_base(format!("{}/oauth2", server.base))
+        .build()
+        .expect("Could not build client");
+
+    if let Err((e, _)) = client.client_auth("id".into(), SECRET.into()).await {
+        panic!("Could not authorize against mock server: {}", e);
+    }
+
+    let lines = lines_about(&server);
+    assert_redacted(&lines);
+    assert!(lines.iter().all(|(level, _)| *level == log::Level::Info));
+}
@@ -0,0 +1,108 @@
+#![cfg(feature = "loopback")]
+
+use std::time::Duration;
+use twitch_api_rs::auth::authorization_code::{
+    AuthorizeUrl, CallbackError, CsrfState, ResponseType,
+};
+use twitch_api_rs::auth::loopback::*;
+use twitch_api_rs::auth::scopes::Scope;
+
+async fn get(url: String) -> (u16, String) {
+    let resp = reqwest::get(&url).await.unwrap();
+    (resp.status().as_u16(), resp.text().await.unwrap())
+}
+
+#[tokio::test]
+async fn code_is_returned_after_state_check() {
+    let listener = LoopbackServer::new()
+        .path("callback")
+        .success_page("<p>done</p>")
+        .bind()
+        .await
+        .unwrap();
+
+    let redirect_uri = listener.redirect_uri();
+    assert!(redirect_uri.starts_with("http://127.0.0.1:"));
+    assert!(redirect_uri.ends_with("/callback"));
+
+    let authorize = AuthorizeUrl::new(ResponseType::Code, "client_id".to_string(), redirect_uri);
+    let state = authorize.csrf_state().secret().to_string();
+    let base = format!("http://{}", listener.local_addr());
+
+    let browser = tokio::spawn(async move {
+        let other = get(format!("{}/favicon.ico", base)).await;
+        let callback = get(format!(
+            "{}/callback?code=abc&scope=clips%3Aedit&state={}",
+            base, state
+        ))
+        .await;
+        (other, callback)
+    });
+
+    let code = listener
+        .wait_for_code(authorize.csrf_state())
+        .await
+        .expect("No code received");
+    assert_eq!(code.code, "abc");
+    assert!(code.scopes.contains(Scope::ClipsEdit));
+
+    let (other, callback) = browser.await.unwrap();
+    assert_eq!(other.0, 404);
+    assert_eq!(callback, (200, "<p>done</p>".to_string()));
+}
+
+#[tokio::test]
+async fn forged_redirect_is_rejected() {
+    let listener = LoopbackServer::new().bind().await.unwrap();
+    let base = format!("http://{}", listener.local_addr());
+
+    let browser = tokio::spawn(get(format!("{}/?code=abc&state=forged", base)));
+
+    match listener.wait_for_code(&CsrfState::new("expected")).await {
+        Err(LoopbackError::Callback(CallbackError::StateMismatch)) => (),
+        other => panic!("Expected a state mismatch but got {:?}", other),
+    }
+
+    assert_eq!(browser.await.unwrap().0, 400);
+}
+
+#[tokio::test]
+async fn waiting_times_out() {
+    let listener = LoopbackServer::new()
+        .timeout(Duration::from_millis(50))
+        .bind()
+        .await
+        .unwrap();
+
+    match listener.wait_for_code(&CsrfState::random()).await {
+        Err(LoopbackError::Timeout(timeout)) => assert_eq!(timeout, Duration::from_millis(50)),
+        other => panic!("Expected a timeout but got {:?}", other),
+    }
+}
+
+#[tokio::test]
+async fn idle_connections_are_dropped() {
+    let listener = LoopbackServer::new()
+        .read_timeout(Duration::from_millis(50))
+        .bind()
+        .await
+        .unwrap();
+    let addr = listener.local_addr();
+    let state = CsrfState::random();
+    let secret = state.secret().to_string();
+
+    let browser = tokio::spawn(async move {
+        // Preconnected socket that never sends a request
+        let idle = tokio::net::TcpStream::connect(addr).await.unwrap();
+        let callback = get(format!("http://{}/?code=abc&state={}", addr, secret)).await;
+        drop(idle);
+        callback
+    });
+
+    let code = listener
+        .wait_for_code(&state)
+        .await
+        .expect("No code received");
+    assert_eq!(code.code, "abc");
+    assert_eq!(browser.await.unwrap().0, 200);
+}
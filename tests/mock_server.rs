@@ -0,0 +1,163 @@
+#![allow(dead_code)]
+
+//! Minimal http server on localhost that replies with canned responses, used to test
+//! the client without talking to twitch
+
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpListener;
+
+/// A response the server will send
+#[derive(Debug, Clone)]
+pub struct Canned {
+    pub status: u16,
+    pub headers: Vec<(String, String)>,
+    pub body: String,
+}
+
+impl Canned {
+    pub fn json(status: u16, body: &str) -> Self {
+        Self {
+            status,
+            headers: vec![],
+            body: body.to_string(),
+        }
+    }
+
+    pub fn header(mut self, name: &str, value: &str) -> Self {
+        self.headers.push((name.to_string(), value.to_string()));
+        self
+    }
+}
+
+/// A request the server received
+#[derive(Debug, Clone)]
+pub struct Received {
+    /// For example `GET /helix/users?login=foo HTTP/1.1`
+    pub request_line: String,
+    /// Header names are lowercase
+    pub headers: Vec<(String, String)>,
+    pub body: String,
+}
+
+impl Received {
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(n, _)| n == &name.to_lowercase())
+            .map(|(_, v)| v.as_str())
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct MockServer {
+    /// `http://127.0.0.1:<port>`
+    pub base: String,
+    received: Arc<Mutex<Vec<Received>>>,
+    responses: Arc<Mutex<VecDeque<Canned>>>,
+}
+
+impl MockServer {
+    /// Start a server that answers requests with `responses` in order, then with 404
+    pub async fn start(responses: Vec<Canned>) -> Self {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let base = format!("http://{}", listener.local_addr().unwrap());
+
+        let server = Self {
+            base,
+            received: Arc::new(Mutex::new(Vec::new())),
+            responses: Arc::new(Mutex::new(responses.into())),
+        };
+
+        let handle = server.clone();
+        tokio::spawn(async move {
+            loop {
+                let (mut stream, _) = match listener.accept().await {
+                    Ok(conn) => conn,
+                    Err(_) => return,
+                };
+
+                let received = match read_request(&mut stream).await {
+                    Some(received) => received,
+                    None => continue,
+                };
+                handle.received.lock().unwrap().push(received);
+
+                let canned = handle
+                    .responses
+                    .lock()
+                    .unwrap()
+                    .pop_front()
+                    .unwrap_or_else(|| {
+                        Canned::json(404, r#"{"error":"Not Found","status":404,"message":""}"#)
+                    });
+
+                let mut out = format!(
+                    "HTTP/1.1 {} Mock\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n",
+                    canned.status,
+                    canned.body.len()
+                );
+                for (name, value) in canned.headers {
+                    out.push_str(&format!("{}: {}\r\n", name, value));
+                }
+                out.push_str("\r\n");
+                out.push_str(&canned.body);
+
+                let _ = stream.write_all(out.as_bytes()).await;
+                let _ = stream.shutdown().await;
+            }
+        });
+
+        server
+    }
+
+    /// The requests received so far
+    pub fn received(&self) -> Vec<Received> {
+        self.received.lock().unwrap().clone()
+    }
+}
+
+async fn read_request(stream: &mut tokio::net::TcpStream) -> Option<Received> {
+    let mut buf = Vec::new();
+    let mut chunk = [0u8; 1024];
+
+    let header_end = loop {
+        let n = stream.read(&mut chunk).await.ok()?;
+        if n == 0 {
+            return None;
+        }
+        buf.extend_from_slice(&chunk[..n]);
+        if let Some(pos) = buf.windows(4).position(|w| w == b"\r\n\r\n") {
+            break pos + 4;
+        }
+    };
+
+    let head = String::from_utf8_lossy(&buf[..header_end]).to_string();
+    let mut lines = head.split("\r\n");
+    let request_line = lines.next()?.to_string();
+    let headers: Vec<(String, String)> = lines
+        .filter_map(|l| l.split_once(": "))
+        .map(|(n, v)| (n.to_lowercase(), v.to_string()))
+        .collect();
+
+    let length: usize = headers
+        .iter()
+        .find(|(n, _)| n == "content-length")
+        .and_then(|(_, v)| v.parse().ok())
+        .unwrap_or(0);
+
+    while buf.len() < header_end + length {
+        let n = stream.read(&mut chunk).await.ok()?;
+        if n == 0 {
+            break;
+        }
+        buf.extend_from_slice(&chunk[..n]);
+    }
+
+    Some(Received {
+        request_line,
+        headers,
+        body: String::from_utf8_lossy(&buf[header_end..]).to_string(),
+    })
+}
@@ -0,0 +1,136 @@
+mod mock_server;
+
+use mock_server::{Canned, MockServer};
+use twitch_api_rs::auth::refresh_token::*;
+use twitch_api_rs::auth::scopes::{Scope, ScopeSet};
+use twitch_api_rs::auth::store::*;
+use twitch_api_rs::auth::user_token::UserAuthToken;
+use twitch_api_rs::auth::AuthToken;
+use twitch_api_rs::client::{Client, RequestErrorType};
+use twitch_api_rs::requests::{ErrorCodes, FailureStatus, Request, RequestError};
+
+fn current() -> UserAuthToken {
+    let mut scopes = ScopeSet::new();
+    scopes.insert(Scope::UserReadEmail);
+
+    UserAuthToken::new(
+        "access".to_string(),
+        Some("refresh".to_string()),
+        "client_id",
+        scopes,
+    )
+    .with_user("477906794", "thehoodlum12")
+}
+
+#[test]
+fn request_needs_refresh_token() {
+    let mut token = current();
+    assert!(RefreshTokenRequest::from_token(&token, "client_secret".to_string()).is_some());
+
+    token.refresh_token = None;
+    assert!(RefreshTokenRequest::from_token(&token, "client_secret".to_string()).is_none());
+
+    let mut req = RefreshTokenRequest::builder();
+    req.set_client_id("client_id".to_string());
+    match req.ready() {
+        Err(RequestError::MalformedRequest(msg)) => assert!(msg.contains("refresh_token")),
+        other => panic!("Expected a malformed request but got {:?}", other),
+    }
+
+    // Public clients do not need a secret
+    req.set_refresh_token("refresh");
+    assert!(req.ready().is_ok());
+}
+
+#[test]
+fn invalid_refresh_token_is_known() {
+    let status = |message: &str| FailureStatus {
+        error: Some("Bad Request".to_string()),
+        status: 400,
+        message: message.to_string(),
+    };
+
+    assert_eq!(
+        RefreshTokenErrorCodes::from_status(status("Invalid refresh token"))
+            .unwrap()
+            .status,
+        RefreshTokenErrorCodes::InvalidRefreshToken
+    );
+    assert_eq!(
+        RefreshTokenErrorCodes::from_status(status("missing client id"))
+            .unwrap()
+            .status,
+        RefreshTokenErrorCodes::BadRequestCode
+    );
+}
+
+#[test]
+fn rotated_token_keeps_user_and_scopes() {
+    let resp: RefreshTokenResponse = serde_json::from_str(
+        r#"{"access_token":"new_access","refresh_token":"new_refresh","scope":[],"token_type":"bearer"}"#,
+    )
+    .unwrap();
+    assert_eq!(resp.expires_in, None);
+
+    let token = resp.into_token(&current());
+    assert_eq!(token.token, "new_access");
+    assert_eq!(token.refresh_token.as_deref(), Some("new_refresh"));
+    assert_eq!(
+        token.user_id.as_deref().map(|i| i.as_str()),
+        Some("477906794")
+    );
+    assert!(token.scopes().contains(Scope::UserReadEmail));
+
+    let resp: RefreshTokenResponse = serde_json::from_str(
+        r#"{"access_token":"new_access","refresh_token":"new_refresh","expires_in":5000,"scope":["chat:read"],"token_type":"bearer"}"#,
+    )
+    .unwrap();
+    let token = resp.into_token(&current());
+    assert!(token.scopes().contains(Scope::ChatRead));
+    assert!(!token.scopes().contains(Scope::UserReadEmail));
+}
+
+#[tokio::test]
+async fn client_reports_invalid_refresh_token() {
+    let server = MockServer::start(vec![Canned::json(
+        400,
+        r#"{"error":"Bad Request","status":400,"message":"Invalid refresh token"}"#,
+    )])
+    .await;
+
+    let store = MemoryStore::new();
+    store
+        .save(
+            &TokenKey::User("477906794".to_string().into()),
+            &StoredToken::from_user(&current(), None),
+        )
+        .unwrap();
+
+    let client = match Client::builder()
+        .auth_base(format!("{}/oauth2", server.base))
+        .token_store(store)
+        .build()
+        .unwrap()
+        .restore_user("client_secret".to_string().into(), "477906794")
+    {
+        Ok(client) => client,
+        Err((e, _)) => panic!("Could not restore user: {}", e),
+    };
+
+    let err = client.refresh_token().await.unwrap_err();
+    match err.kind() {
+        RequestErrorType::RefreshError {
+            from: RequestError::KnownErrorStatus(e),
+        } => assert_eq!(e.status, RefreshTokenErrorCodes::InvalidRefreshToken),
+        other => panic!("Expected an invalid refresh token but got {:?}", other),
+    }
+
+    let received = server.received();
+    assert!(received[0]
+        .request_line
+        .contains("grant_type=refresh_token"));
+    assert!(received[0].request_line.contains("refresh_token=refresh"));
+    assert!(received[0]
+        .request_line
+        .contains("client_secret=client_secret"));
+}
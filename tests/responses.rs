@@ -0,0 +1,195 @@
+//! Deserialization of response objects, run with `--features strict` to also check that
+//! unknown or missing fields are rejected
+
+use twitch_api_rs::resource::channels::get_channel_information::*;
+use twitch_api_rs::resource::clips::get_clips::*;
+use twitch_api_rs::resource::clips::ClipInfo;
+use twitch_api_rs::resource::users::get_users::*;
+use twitch_api_rs::values::broadcasters::BroadcasterType;
+use twitch_api_rs::values::users::UserType;
+
+const CLIP: &str = r#"{
+    "id": "AwkwardHelplessSalamanderSwiftRage",
+    "url": "https://clips.twitch.tv/AwkwardHelplessSalamanderSwiftRage",
+    "embed_url": "https://clips.twitch.tv/embed?clip=AwkwardHelplessSalamanderSwiftRage",
+    "broadcaster_id": "67955580",
+    "broadcaster_name": "ChewieMelodies",
+    "creator_id": "53834192",
+    "creator_name": "BlackNova03",
+    "video_id": "205586603",
+    "game_id": "488191",
+    "language": "en",
+    "title": "babymetal",
+    "view_count": 10,
+    "created_at": "2017-11-30T22:34:18Z",
+    "thumbnail_url": "https://clips-media-assets.twitch.tv/157589949-preview-480x272.jpg"
+}"#;
+
+const USER: &str = r#"{
+    "id": "141981764",
+    "login": "twitchdev",
+    "display_name": "TwitchDev",
+    "type": "",
+    "broadcaster_type": "partner",
+    "description": "Supporting third-party developers building Twitch integrations",
+    "profile_image_url": "https://static-cdn.jtvnw.net/jtv_user_pictures/profile_image-300x300.png",
+    "offline_image_url": "https://static-cdn.jtvnw.net/jtv_user_pictures/channel_offline_image-1920x1080.png",
+    "view_count": 5980557,
+    "email": "not-real@email.com",
+    "created_at": "2016-12-14T20:32:28Z"
+}"#;
+
+const CHANNEL: &str = r#"{
+    "broadcaster_id": "141981764",
+    "broadcaster_name": "TwitchDev",
+    "broadcaster_language": "en",
+    "game_id": "509670",
+    "game_name": "Science & Technology",
+    "title": "TwitchDev Monthly Update // May 6, 2021"
+}"#;
+
+/// Add a field that this crate does not know about to a json object
+fn with_extra_field(object: &str) -> String {
+    let mut value: serde_json::Value = serde_json::from_str(object).unwrap();
+    value
+        .as_object_mut()
+        .unwrap()
+        .insert("is_new".to_string(), serde_json::Value::Bool(true));
+    value.to_string()
+}
+
+/// Replace the value of a field in a json object
+fn with_field(object: &str, field: &str, new: &str) -> String {
+    let mut value: serde_json::Value = serde_json::from_str(object).unwrap();
+    value.as_object_mut().unwrap().insert(
+        field.to_string(),
+        serde_json::Value::String(new.to_string()),
+    );
+    value.to_string()
+}
+
+/// Remove a field from a json object
+fn without_field(object: &str, field: &str) -> String {
+    let mut value: serde_json::Value = serde_json::from_str(object).unwrap();
+    value.as_object_mut().unwrap().remove(field);
+    value.to_string()
+}
+
+#[test]
+fn known_fields() {
+    let clip: ClipInfo = serde_json::from_str(CLIP).expect("Could not parse clip");
+    assert!(clip.extra.is_empty(), "Clip had extra fields");
+
+    let user: UserDescription = serde_json::from_str(USER).expect("Could not parse user");
+    assert!(user.extra.is_empty(), "User had extra fields");
+
+    let channel: ChannelInformation =
+        serde_json::from_str(CHANNEL).expect("Could not parse channel");
+    assert!(channel.extra.is_empty(), "Channel had extra fields");
+}
+
+#[test]
+fn known_types() {
+    let user: UserDescription = serde_json::from_str(USER).expect("Could not parse user");
+    assert_eq!(user.broadcaster_type, BroadcasterType::Partner);
+    assert_eq!(user.user_type, UserType::Normal);
+
+    let user: UserDescription = serde_json::from_str(&with_field(USER, "type", "global_mod"))
+        .expect("Could not parse user");
+    assert_eq!(user.user_type, UserType::GlobalMod);
+
+    let value = serde_json::to_value(&user).unwrap();
+    assert_eq!(value["type"], "global_mod");
+    assert_eq!(value["broadcaster_type"], "partner");
+}
+
+#[test]
+fn wrapped_response() {
+    let resp: GetClipsResponse =
+        serde_json::from_str(&format!(r#"{{ "data": [{}], "pagination": {{}} }}"#, CLIP))
+            .expect("Could not parse clips response");
+
+    assert_eq!(resp.clips.len(), 1);
+}
+
+#[cfg(not(feature = "strict"))]
+#[test]
+fn unknown_fields_are_kept() {
+    let clip: ClipInfo =
+        serde_json::from_str(&with_extra_field(CLIP)).expect("Could not parse clip");
+    assert_eq!(
+        clip.extra.get("is_new"),
+        Some(&serde_json::Value::Bool(true))
+    );
+
+    let user: UserDescription =
+        serde_json::from_str(&with_extra_field(USER)).expect("Could not parse user");
+    assert_eq!(
+        user.extra.get("is_new"),
+        Some(&serde_json::Value::Bool(true))
+    );
+
+    let channel: ChannelInformation =
+        serde_json::from_str(&with_extra_field(CHANNEL)).expect("Could not parse channel");
+    assert_eq!(
+        channel.extra.get("is_new"),
+        Some(&serde_json::Value::Bool(true))
+    );
+
+    // And are written back out
+    let value = serde_json::to_value(&channel).unwrap();
+    assert_eq!(value["is_new"], serde_json::Value::Bool(true));
+}
+
+#[cfg(not(feature = "strict"))]
+#[test]
+fn unknown_types_are_kept() {
+    let user: UserDescription =
+        serde_json::from_str(&with_field(USER, "broadcaster_type", "ambassador"))
+            .expect("Could not parse user");
+    assert_eq!(
+        user.broadcaster_type,
+        BroadcasterType::Unknown("ambassador".into())
+    );
+    assert_eq!(user.broadcaster_type.to_string(), "ambassador");
+
+    // And are written back out
+    let value = serde_json::to_value(&user).unwrap();
+    assert_eq!(value["broadcaster_type"], "ambassador");
+}
+
+#[cfg(not(feature = "strict"))]
+#[test]
+fn missing_fields_are_defaulted() {
+    let clip: ClipInfo = serde_json::from_str(&without_field(CLIP, "video_id"))
+        .expect("Could not parse clip without video_id");
+    assert!(clip.video_id.is_empty());
+
+    // Ids are never defaulted
+    assert!(serde_json::from_str::<ClipInfo>(&without_field(CLIP, "id")).is_err());
+    assert!(serde_json::from_str::<UserDescription>(&without_field(USER, "id")).is_err());
+}
+
+#[cfg(feature = "strict")]
+#[test]
+fn strict_rejects_unknown_fields() {
+    assert!(serde_json::from_str::<ClipInfo>(&with_extra_field(CLIP)).is_err());
+    assert!(serde_json::from_str::<UserDescription>(&with_extra_field(USER)).is_err());
+    assert!(serde_json::from_str::<ChannelInformation>(&with_extra_field(CHANNEL)).is_err());
+}
+
+#[cfg(feature = "strict")]
+#[test]
+fn strict_rejects_unknown_types() {
+    assert!(
+        serde_json::from_str::<UserDescription>(&with_field(USER, "type", "moderator")).is_err()
+    );
+}
+
+#[cfg(feature = "strict")]
+#[test]
+fn strict_rejects_missing_fields() {
+    assert!(serde_json::from_str::<ClipInfo>(&without_field(CLIP, "video_id")).is_err());
+    assert!(serde_json::from_str::<UserDescription>(&without_field(USER, "login")).is_err());
+    assert!(serde_json::from_str::<ChannelInformation>(&without_field(CHANNEL, "title")).is_err());
+}
@@ -0,0 +1,125 @@
+mod mock_server;
+
+use mock_server::{Canned, MockServer};
+use std::sync::Arc;
+use twitch_api_rs::auth::revoke::*;
+use twitch_api_rs::auth::scopes::ScopeSet;
+use twitch_api_rs::auth::store::*;
+use twitch_api_rs::auth::user_token::UserAuthToken;
+use twitch_api_rs::client::*;
+use twitch_api_rs::requests::{ErrorCodes, FailureStatus, Request, RequestError};
+
+const INVALID: &str = r#"{"status":400,"message":"Invalid token"}"#;
+
+fn user_token() -> UserAuthToken {
+    UserAuthToken::new(
+        "access".to_string(),
+        Some("refresh".to_string()),
+        "client_id",
+        ScopeSet::new(),
+    )
+    .with_user("477906794", "thehoodlum12")
+}
+
+fn client(server: &MockServer, store: Arc<MemoryStore>) -> Client<Unauthorized> {
+    Client::builder()
+        .auth_base(format!("{}/oauth2", server.base))
+        .token_store(store)
+        .build()
+        .unwrap()
+}
+
+#[test]
+fn request_is_built_from_tokens() {
+    let req = RevokeRequest::from(&user_token());
+    assert!(req.ready().is_ok());
+
+    match RevokeRequest::builder().ready() {
+        Err(RequestError::MalformedRequest(msg)) => assert!(msg.contains("client_id")),
+        other => panic!("Expected a malformed request but got {:?}", other),
+    }
+
+    let status = |status: u16, message: &str| FailureStatus {
+        error: None,
+        status,
+        message: message.to_string(),
+    };
+    assert_eq!(
+        RevokeErrorCodes::from_status(status(400, "Invalid token"))
+            .unwrap()
+            .status,
+        RevokeErrorCodes::InvalidToken
+    );
+    assert_eq!(
+        RevokeErrorCodes::from_status(status(404, "client does not exist"))
+            .unwrap()
+            .status,
+        RevokeErrorCodes::ClientNotFound
+    );
+}
+
+#[tokio::test]
+async fn revoked_user_is_removed_from_store() {
+    let server = MockServer::start(vec![Canned::json(200, "")]).await;
+
+    let store = Arc::new(MemoryStore::new());
+    let key = TokenKey::User("477906794".to_string().into());
+    store
+        .save(&key, &StoredToken::from_user(&user_token(), None))
+        .unwrap();
+
+    let client = match client(&server, store.clone())
+        .restore_user("client_secret".to_string().into(), "477906794")
+    {
+        Ok(client) => client,
+        Err((e, _)) => panic!("Could not restore user: {}", e),
+    };
+
+    if let Err((e, _)) = client.revoke().await {
+        panic!("Could not revoke token: {}", e);
+    }
+
+    assert_eq!(store.load(&key).unwrap(), None);
+
+    let received = server.received();
+    assert!(received[0].request_line.starts_with("POST /oauth2/revoke?"));
+    assert!(received[0].request_line.contains("client_id=client_id"));
+    assert!(received[0].request_line.contains("token=access"));
+}
+
+#[tokio::test]
+async fn failed_revoke_keeps_token() {
+    let server =
+        MockServer::start(vec![Canned::json(400, INVALID), Canned::json(400, INVALID)]).await;
+
+    let store = Arc::new(MemoryStore::new());
+    let client = client(&server, store.clone());
+
+    let pool = client.token_pool("client_secret".to_string().into());
+    pool.insert("477906794", user_token(), None);
+
+    match pool.revoke("477906794").await {
+        Err(PoolError::RevokeFailed { from, .. }) => match from.kind() {
+            RequestErrorType::RevokeError {
+                from: RequestError::KnownErrorStatus(e),
+            } => assert_eq!(e.status, RevokeErrorCodes::InvalidToken),
+            other => panic!("Expected an invalid token but got {:?}", other),
+        },
+        other => panic!("Expected the revoke to fail but got {:?}", other),
+    }
+    assert!(pool.contains("477906794"));
+
+    let key = TokenKey::User("477906794".to_string().into());
+    assert!(store.load(&key).unwrap().is_some());
+
+    let client = match client.restore_user("client_secret".to_string().into(), "477906794") {
+        Ok(client) => client,
+        Err((e, _)) => panic!("Could not restore user: {}", e),
+    };
+    let client = match client.revoke().await {
+        Ok(_) => panic!("Revoked an invalid token"),
+        Err((_, client)) => client,
+    };
+    assert_eq!(client.token().token, "access");
+    assert!(store.load(&key).unwrap().is_some());
+}
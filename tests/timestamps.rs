@@ -0,0 +1,193 @@
+#![cfg(any(feature = "chrono", feature = "time"))]
+
+use std::sync::Arc;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use twitch_api_rs::auth::client_credentials::ClientAuthToken;
+use twitch_api_rs::requests::*;
+use twitch_api_rs::values::*;
+
+type Token = Arc<ClientAuthToken>;
+
+fn token() -> Token {
+    Arc::new(ClientAuthToken::new(
+        "not_a_token".to_string(),
+        "not_a_client".to_string(),
+    ))
+}
+
+fn unix(secs: u64) -> SystemTime {
+    UNIX_EPOCH + Duration::from_secs(secs)
+}
+
+#[test]
+fn times_are_checked_on_parse() {
+    let time: RFC3339Time = serde_json::from_str("\"2021-03-26T19:02:15Z\"").unwrap();
+    assert_eq!(time.to_system_time(), Ok(unix(1616785335)));
+    assert_eq!(
+        serde_json::to_string(&time).unwrap(),
+        "\"2021-03-26T19:02:15Z\""
+    );
+
+    assert!(serde_json::from_str::<RFC3339Time>("\"yesterday\"").is_err());
+    assert!(serde_json::from_str::<StartedAt>("\"2021-03-26 19:02\"").is_err());
+
+    // Values that did not come from twitch are only checked on request
+    let time = RFC3339Time::from("yesterday");
+    assert_eq!(time.validate(), Err(InvalidTime("yesterday".into())));
+    assert_eq!(
+        RFC3339Time::parse("yesterday"),
+        Err(InvalidTime("yesterday".into()))
+    );
+}
+
+#[test]
+fn times_are_formatted_the_same_way() {
+    let time = unix(1616785335);
+    let cases = [
+        (time, "2021-03-26T19:02:15Z"),
+        (
+            time + Duration::from_millis(250),
+            "2021-03-26T19:02:15.250Z",
+        ),
+        (
+            time + Duration::from_micros(250),
+            "2021-03-26T19:02:15.000250Z",
+        ),
+        (
+            time + Duration::from_nanos(1),
+            "2021-03-26T19:02:15.000000001Z",
+        ),
+        (unix(951782400), "2000-02-29T00:00:00Z"),
+        (
+            UNIX_EPOCH - Duration::from_millis(500),
+            "1969-12-31T23:59:59.500Z",
+        ),
+    ];
+
+    for (time, text) in cases.iter() {
+        let formatted = RFC3339Time::from(*time);
+        assert_eq!(formatted.as_str(), *text);
+        assert_eq!(formatted.to_system_time(), Ok(*time));
+    }
+}
+
+#[test]
+#[should_panic(expected = "Years outside of 0..=9999 cannot be written as RFC3339")]
+fn years_after_9999_are_not_formatted() {
+    // 10000-01-01T00:00:00Z
+    let _ = RFC3339Time::from(unix(253402300800));
+}
+
+#[test]
+fn times_are_ordered_by_instant() {
+    let mut times: Vec<RFC3339Time> = vec![
+        "2021-03-26T20:00:00+02:00".into(),
+        "2021-03-26T19:02:15Z".into(),
+        "2021-03-26T18:00:00Z".into(),
+    ];
+    times.sort();
+
+    assert_eq!(
+        times.iter().map(|t| t.as_str()).collect::<Vec<_>>(),
+        vec![
+            "2021-03-26T18:00:00Z",
+            "2021-03-26T20:00:00+02:00",
+            "2021-03-26T19:02:15Z",
+        ]
+    );
+}
+
+#[test]
+fn periods_from_ranges_and_durations() {
+    let period = Period::from(unix(1616785335)..unix(1616785335 + 3600));
+    assert_eq!(period.started_at.as_str(), "2021-03-26T19:02:15Z");
+    assert_eq!(period.ended_at.as_str(), "2021-03-26T20:02:15Z");
+    assert_eq!(
+        Period::starting_at(unix(1616785335), Duration::from_secs(3600)),
+        period
+    );
+
+    let last = Period::last(Duration::from_secs(60 * 60 * 24 * 7));
+    let (start, end) = (
+        last.started_at.to_system_time().unwrap(),
+        last.ended_at.to_system_time().unwrap(),
+    );
+    assert_eq!(
+        end.duration_since(start).unwrap(),
+        Duration::from_secs(60 * 60 * 24 * 7)
+    );
+}
+
+#[test]
+fn clips_period_is_checked() {
+    use twitch_api_rs::resource::clips::get_clips::*;
+
+    let mut req = GetClipsRequest::<Token>::builder();
+    req.set_auth(token())
+        .set_broadcaster_id("477906794")
+        .set_window(unix(1616785335)..unix(1616785335 + 3600));
+    assert!(req.ready().is_ok());
+
+    let params = serde_json::to_value(&req).unwrap();
+    assert_eq!(params["started_at"], "2021-03-26T19:02:15Z");
+    assert_eq!(params["ended_at"], "2021-03-26T20:02:15Z");
+
+    req.set_period(unix(1616785335), unix(1616785335 - 1));
+    assert!(matches!(
+        req.ready(),
+        Err(RequestError::MalformedRequest(_))
+    ));
+}
+
+#[test]
+#[should_panic(expected = "\"last tuesday\" is not a RFC3339 time")]
+fn clips_period_is_checked_when_set() {
+    use twitch_api_rs::resource::clips::get_clips::*;
+
+    GetClipsRequest::<Token>::builder().set_period("last tuesday", unix(1616785335));
+}
+
+#[cfg(feature = "chrono")]
+#[test]
+fn chrono_datetimes() {
+    use chrono::{Duration, FixedOffset, TimeZone, Utc};
+
+    let time: RFC3339Time = serde_json::from_str("\"2021-03-26T21:02:15+02:00\"").unwrap();
+    let parsed = time.to_chrono().unwrap();
+    assert_eq!(
+        parsed,
+        Utc.with_ymd_and_hms(2021, 3, 26, 19, 2, 15).unwrap()
+    );
+    assert_eq!(
+        RFC3339Time::from(parsed + Duration::milliseconds(250)).as_str(),
+        "2021-03-26T19:02:15.250Z"
+    );
+
+    let start = FixedOffset::east_opt(2 * 3600)
+        .unwrap()
+        .with_ymd_and_hms(2021, 3, 26, 21, 2, 15)
+        .unwrap();
+    let period = Period::from(start..start + Duration::hours(1));
+    assert_eq!(period.started_at.as_str(), "2021-03-26T19:02:15Z");
+    assert_eq!(period.ended_at.as_str(), "2021-03-26T20:02:15Z");
+}
+
+#[cfg(feature = "time")]
+#[test]
+fn time_datetimes() {
+    use time::{Duration, OffsetDateTime, UtcOffset};
+
+    let time: RFC3339Time = serde_json::from_str("\"2021-03-26T21:02:15+02:00\"").unwrap();
+    let parsed = time.to_offset_date_time().unwrap();
+    assert_eq!(parsed.offset(), UtcOffset::from_hms(2, 0, 0).unwrap());
+    assert_eq!(parsed.unix_timestamp(), 1616785335);
+    assert_eq!(
+        RFC3339Time::from(parsed + Duration::milliseconds(250)).as_str(),
+        "2021-03-26T19:02:15.250Z"
+    );
+
+    let start = OffsetDateTime::from_unix_timestamp(1616785335).unwrap();
+    let period = Period::from(start..start + Duration::hours(1));
+    assert_eq!(period.started_at.as_str(), "2021-03-26T19:02:15Z");
+    assert_eq!(period.ended_at.as_str(), "2021-03-26T20:02:15Z");
+}
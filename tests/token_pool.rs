@@ -0,0 +1,174 @@
+mod mock_server;
+
+use mock_server::{Canned, MockServer};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use twitch_api_rs::auth::scopes::{Scope, ScopeRequirement, ScopeSet};
+use twitch_api_rs::auth::user_token::UserAuthToken;
+use twitch_api_rs::client::*;
+use twitch_api_rs::requests::{Request, RequestError};
+use twitch_api_rs::resource::channels::get_channel_information::*;
+
+const CHANNEL: &str = r#"{"data":[{"broadcaster_id":"1","broadcaster_name":"one","broadcaster_language":"en","game_id":"","game_name":"","title":"hi"}]}"#;
+const UNAUTHORIZED: &str =
+    r#"{"error":"Unauthorized","status":401,"message":"Invalid OAuth token"}"#;
+
+fn user_token(token: &str, refresh: &str, scopes: &[Scope]) -> UserAuthToken {
+    let mut set = ScopeSet::new();
+    scopes.iter().for_each(|s| set.insert(*s));
+    UserAuthToken::new(
+        token.to_string(),
+        Some(refresh.to_string()),
+        "client_id",
+        set,
+    )
+}
+
+fn refreshed(token: &str, refresh: &str) -> Canned {
+    Canned::json(
+        200,
+        &format!(
+            r#"{{"access_token":"{}","refresh_token":"{}","expires_in":5000,"scope":["channel:read:editors"],"token_type":"bearer"}}"#,
+            token, refresh
+        ),
+    )
+}
+
+fn pool_for(server: &MockServer) -> TokenPool {
+    Client::builder()
+        .api_base(format!("{}/helix", server.base))
+        .auth_base(format!("{}/oauth2", server.base))
+        .retry_policy(RetryPolicy::none())
+        .build()
+        .unwrap()
+        .token_pool("client_secret".to_string().into())
+}
+
+fn channel_info(broadcaster: &str) -> GetChannelInformationRequest<Arc<UserAuthToken>> {
+    let mut req = GetChannelInformationRequest::builder();
+    req.set_broadcaster_id(broadcaster);
+    req
+}
+
+#[tokio::test]
+async fn missing_tokens_and_scopes_are_reported() {
+    let server = MockServer::start(vec![]).await;
+    let pool = pool_for(&server);
+
+    pool.insert(
+        "1",
+        user_token("one", "r1", &[Scope::ChannelReadEditors]),
+        None,
+    );
+    assert!(pool.contains("1"));
+    assert_eq!(pool.len(), 1);
+
+    match pool.get("2").await {
+        Err(PoolError::MissingToken(user)) => assert_eq!(&*user, "2"),
+        other => panic!("Expected a missing token but got {:?}", other),
+    }
+
+    match pool
+        .get_with_scopes(
+            "1",
+            ScopeRequirement::all_of([Scope::ChannelReadEditors, Scope::ClipsEdit]),
+        )
+        .await
+    {
+        Err(PoolError::MissingScopes { user, missing }) => {
+            assert_eq!(&*user, "1");
+            assert_eq!(missing.requirement(), &Scope::ClipsEdit.into());
+        }
+        other => panic!("Expected missing scopes but got {:?}", other),
+    }
+
+    match pool.send(channel_info("1"), Scope::ClipsEdit).await {
+        Err(RequestError::ScopesError(missing)) => assert_eq!(missing, vec!["clips:edit"]),
+        other => panic!("Expected a scopes error but got {:?}", other),
+    }
+
+    let token = pool
+        .get_with_scopes("1", Scope::ChannelReadEditors)
+        .await
+        .unwrap();
+    assert_eq!(token.token, "one");
+    assert_eq!(token.user_id.as_deref().map(|u| u.as_str()), Some("1"));
+
+    assert!(server.received().is_empty());
+}
+
+#[tokio::test]
+async fn tokens_are_picked_by_broadcaster() {
+    let server = MockServer::start(vec![Canned::json(200, CHANNEL)]).await;
+    let pool = pool_for(&server);
+
+    pool.insert("1", user_token("one", "r1", &[]), None);
+    pool.insert("2", user_token("two", "r2", &[]), None);
+
+    let info = pool
+        .send(channel_info("2"), ScopeRequirement::none())
+        .await
+        .expect("Request failed");
+    assert_eq!(info.channels.len(), 1);
+
+    let received = server.received();
+    assert_eq!(received[0].header("authorization"), Some("Bearer two"));
+
+    match pool.send(channel_info("3"), ScopeRequirement::none()).await {
+        Err(RequestError::UnknownError(_)) => (),
+        other => panic!("Expected a missing token but got {:?}", other),
+    }
+    assert_eq!(server.received().len(), 1);
+}
+
+#[tokio::test]
+async fn rejected_token_is_refreshed_on_its_own() {
+    let server = MockServer::start(vec![
+        Canned::json(401, UNAUTHORIZED),
+        refreshed("one_new", "r1_new"),
+        Canned::json(200, CHANNEL),
+    ])
+    .await;
+
+    let seen = Arc::new(Mutex::new(Vec::new()));
+    let record = seen.clone();
+    let pool = pool_for(&server).on_token_refresh(move |token| {
+        record.lock().unwrap().push(token.token.clone());
+    });
+
+    pool.insert("1", user_token("one", "r1", &[]), None);
+    pool.insert("2", user_token("two", "r2", &[]), None);
+
+    pool.send(channel_info("1"), ScopeRequirement::none())
+        .await
+        .expect("Request was not replayed");
+
+    assert_eq!(pool.get("1").await.unwrap().token, "one_new");
+    assert_eq!(pool.get("2").await.unwrap().token, "two");
+    assert_eq!(*seen.lock().unwrap(), vec!["one_new".to_string()]);
+
+    let received = server.received();
+    assert_eq!(received.len(), 3);
+    assert!(received[1].request_line.contains("refresh_token=r1"));
+    assert_eq!(received[2].header("authorization"), Some("Bearer one_new"));
+}
+
+#[tokio::test]
+async fn expiring_token_is_refreshed_before_use() {
+    let server = MockServer::start(vec![refreshed("two_new", "r2_new")]).await;
+    let pool = pool_for(&server);
+
+    pool.insert("1", user_token("one", "r1", &[]), None);
+    pool.insert(
+        "2",
+        user_token("two", "r2", &[]),
+        Some(Duration::from_secs(10)),
+    );
+
+    assert_eq!(pool.get("1").await.unwrap().token, "one");
+    assert_eq!(pool.get("2").await.unwrap().token, "two_new");
+    assert_eq!(server.received().len(), 1);
+
+    assert!(pool.remove("2").is_some());
+    assert_eq!(pool.users(), vec!["1".to_string().into()]);
+}
@@ -0,0 +1,208 @@
+mod mock_server;
+
+use mock_server::{Canned, MockServer};
+use std::sync::Arc;
+use std::time::Duration;
+use twitch_api_rs::auth::scopes::{Scope, ScopeSet};
+use twitch_api_rs::auth::store::*;
+use twitch_api_rs::auth::user_token::UserAuthToken;
+use twitch_api_rs::auth::AuthToken;
+use twitch_api_rs::client::*;
+
+const USERS: &str = r#"{"data":[{"id":"477906794","login":"thehoodlum12","display_name":"TheHoodlum12","type":"","broadcaster_type":"","description":"","profile_image_url":"","offline_image_url":"","view_count":0,"created_at":"2019-11-18T00:47:34Z"}]}"#;
+const UNAUTHORIZED: &str =
+    r#"{"error":"Unauthorized","status":401,"message":"Invalid OAuth token"}"#;
+
+fn user_token() -> StoredToken {
+    let mut scopes = ScopeSet::new();
+    scopes.insert(Scope::UserReadEmail);
+
+    let token = UserAuthToken::new(
+        "access".to_string(),
+        Some("refresh".to_string()),
+        "client_id",
+        scopes,
+    )
+    .with_user("477906794", "thehoodlum12");
+
+    StoredToken::from_user(&token, Some(Duration::from_secs(5000)))
+}
+
+fn check_store(store: &dyn TokenStore) {
+    let key = TokenKey::User("477906794".to_string().into());
+    assert_eq!(store.load(&key).unwrap(), None);
+
+    let token = user_token();
+    store.save(&key, &token).unwrap();
+    store
+        .save(&TokenKey::App("client_id".to_string().into()), &token)
+        .unwrap();
+    assert_eq!(store.load(&key).unwrap(), Some(token.clone()));
+
+    let restored = token.to_user();
+    assert_eq!(restored.refresh_token.as_deref(), Some("refresh"));
+    assert!(restored.scopes().contains(Scope::UserReadEmail));
+    assert!(token.expires_in().unwrap() > Duration::from_secs(4990));
+
+    store.delete(&key).unwrap();
+    assert_eq!(store.load(&key).unwrap(), None);
+    assert!(store
+        .load(&TokenKey::App("client_id".to_string().into()))
+        .unwrap()
+        .is_some());
+}
+
+#[test]
+fn memory_store() {
+    check_store(&MemoryStore::new());
+}
+
+#[test]
+fn json_file_store() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("tokens.json");
+    let store = JsonFileStore::new(&path);
+
+    check_store(&store);
+
+    // Only the store file is left behind
+    let files: Vec<_> = std::fs::read_dir(dir.path()).unwrap().collect();
+    assert_eq!(files.len(), 1);
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        let mode = std::fs::metadata(&path).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, 0o600);
+    }
+
+    // A temporary file left behind with looser permissions is not reused
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        let stale = dir
+            .path()
+            .join(format!("tokens.json.{}.tmp", std::process::id()));
+        std::fs::write(&stale, b"{}").unwrap();
+        std::fs::set_permissions(&stale, std::fs::Permissions::from_mode(0o644)).unwrap();
+
+        store
+            .delete(&TokenKey::User("nobody".to_string().into()))
+            .unwrap();
+
+        let mode = std::fs::metadata(&path).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, 0o600);
+        assert!(!stale.exists());
+    }
+
+    // A new store reads what the first one wrote
+    let reopened = JsonFileStore::new(&path);
+    assert!(reopened
+        .load(&TokenKey::App("client_id".to_string().into()))
+        .unwrap()
+        .is_some());
+}
+
+#[tokio::test]
+async fn app_token_is_saved_and_reused() {
+    let server = MockServer::start(vec![Canned::json(
+        200,
+        r#"{"access_token":"abc","expires_in":5000,"token_type":"bearer"}"#,
+    )])
+    .await;
+    let store = Arc::new(MemoryStore::new());
+
+    for _ in 0..2 {
+        let client = Client::builder()
+            .auth_base(format!("{}/oauth2", server.base))
+            .token_store(store.clone())
+            .build()
+            .unwrap();
+
+        let client = match client
+            .client_auth("client_id".into(), "client_secret".into())
+            .await
+        {
+            Ok(client) => client,
+            Err((e, _)) => panic!("Could not authorize: {}", e),
+        };
+
+        assert_eq!(client.token().token, "abc");
+    }
+
+    assert_eq!(server.received().len(), 1);
+}
+
+#[tokio::test]
+async fn rotated_user_tokens_are_saved() {
+    let server = MockServer::start(vec![
+        Canned::json(401, UNAUTHORIZED),
+        Canned::json(
+            200,
+            r#"{"access_token":"new_access","refresh_token":"new_refresh","expires_in":5000,"scope":["user:read:email"],"token_type":"bearer"}"#,
+        ),
+        Canned::json(200, USERS),
+    ])
+    .await;
+
+    let store = Arc::new(MemoryStore::new());
+    let key = TokenKey::User("477906794".to_string().into());
+    store.save(&key, &user_token()).unwrap();
+
+    let client = Client::builder()
+        .api_base(format!("{}/helix", server.base))
+        .auth_base(format!("{}/oauth2", server.base))
+        .token_store(store.clone())
+        .build()
+        .unwrap();
+
+    let client = match client.restore_user("client_secret".to_string().into(), "477906794") {
+        Ok(client) => client,
+        Err((e, _)) => panic!("Could not restore user: {}", e),
+    };
+    assert_eq!(&**client.login(), "thehoodlum12");
+
+    client.users().me().await.expect("Request failed");
+
+    let saved = store.load(&key).unwrap().unwrap();
+    assert_eq!(saved.access_token, "new_access");
+    assert_eq!(saved.refresh_token.as_deref(), Some("new_refresh"));
+    assert_eq!(
+        saved.login.as_deref().map(|l| l.as_str()),
+        Some("thehoodlum12")
+    );
+}
+
+#[test]
+fn restoring_unknown_user_fails() {
+    let client = Client::builder()
+        .token_store(MemoryStore::new())
+        .build()
+        .unwrap();
+
+    assert!(client
+        .restore_user("client_secret".to_string().into(), "477906794")
+        .is_err());
+}
+
+#[test]
+fn restored_app_token_keeps_issue_time() {
+    let now = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap()
+        .as_secs();
+    let token: StoredToken = serde_json::from_value(serde_json::json!({
+        "access_token": "abc",
+        "client_id": "client_id",
+        "issued_at": now - 100,
+        "expires_at": now + 4900,
+    }))
+    .unwrap();
+
+    let restored = token.to_app();
+    assert!(restored.issued_at().elapsed() >= Duration::from_secs(99));
+    assert_eq!(
+        restored.expires_at().unwrap() - restored.issued_at(),
+        Duration::from_secs(5000)
+    );
+}
@@ -2,30 +2,18 @@ mod common;
 
 #[tokio::test]
 async fn get_users_correct() {
-    let (client_id, client_secret) = crate::common::get_id_secret();
+    common::init();
 
-    use twitch_api_rs::auth::client_credentials::*;
     use twitch_api_rs::requests::Request;
     use twitch_api_rs::resource::users::get_users::*;
 
-    let client = reqwest::Client::new();
-
-    let resp = match ClientAuthRequest::builder()
-        .set_client_id(client_id.clone())
-        .set_client_secret(client_secret)
-        .make_request(&client)
-        .await
-    {
-        Ok(resp) => resp,
-        Err(e) => panic!("Test failed trying to make auth request with error {}", e),
-    };
-
-    let token = ClientAuthToken::from_client(resp, client_id);
+    let client = common::client();
+    let token = common::get_client_auth_token_correct().await;
 
     let resp2 = match GetUsersRequest::builder()
         .set_auth(token)
         .add_login("TheHoodlum12".to_string())
-        .make_request(&client)
+        .make_request(client)
         .await
     {
         Ok(resp) => resp,
@@ -43,29 +31,17 @@ async fn get_users_correct() {
 
 #[tokio::test]
 async fn too_few_arguments() {
-    let (client_id, client_secret) = crate::common::get_id_secret();
+    common::init();
 
-    use twitch_api_rs::auth::client_credentials::*;
     use twitch_api_rs::requests::{Request, RequestError};
     use twitch_api_rs::resource::users::get_users::*;
 
-    let client = reqwest::Client::new();
-
-    let resp = match ClientAuthRequest::builder()
-        .set_client_id(client_id.clone())
-        .set_client_secret(client_secret)
-        .make_request(&client)
-        .await
-    {
-        Ok(resp) => resp,
-        Err(e) => panic!("Test failed trying to make auth request with error {}", e),
-    };
-
-    let token = ClientAuthToken::from_client(resp, client_id);
+    let client = common::client();
+    let token = common::get_client_auth_token_correct().await;
 
     let error = match GetUsersRequest::builder()
         .set_auth(token)
-        .make_request(&client)
+        .make_request(client)
         .await
     {
         Ok(resp) => unreachable!("Should not even try to make the request as there were not enough arguments specified: got {:?}", resp),
@@ -86,25 +62,13 @@ async fn too_few_arguments() {
 
 #[tokio::test]
 async fn too_many_arguments() {
-    let (client_id, client_secret) = crate::common::get_id_secret();
+    common::init();
 
-    use twitch_api_rs::auth::client_credentials::*;
     use twitch_api_rs::requests::{Request, RequestError};
     use twitch_api_rs::resource::users::get_users::*;
 
-    let client = reqwest::Client::new();
-
-    let resp = match ClientAuthRequest::builder()
-        .set_client_id(client_id.clone())
-        .set_client_secret(client_secret)
-        .make_request(&client)
-        .await
-    {
-        Ok(resp) => resp,
-        Err(e) => panic!("Test failed trying to make auth request with error {}", e),
-    };
-
-    let token = ClientAuthToken::from_client(resp, client_id);
+    let client = common::client();
+    let token = common::get_client_auth_token_correct().await;
 
     let mut req = GetUsersRequest::builder();
 
@@ -114,7 +78,7 @@ async fn too_many_arguments() {
     }
 
     let error = match req.set_auth(token)
-        .make_request(&client)
+        .make_request(client)
         .await
     {
         Ok(resp) => unreachable!("Should not even try to make the request as there were not enough arguments specified: got {:?}", resp),
@@ -142,7 +106,7 @@ async fn no_auth_specified() {
     let client = reqwest::Client::new();
 
     let error = match GetUsersRequest::<ClientAuthToken>::builder()
-        .make_request(&client)
+        .make_request(client)
         .await
     {
         Ok(resp) => unreachable!(
@@ -0,0 +1,112 @@
+mod mock_server;
+
+use mock_server::{Canned, MockServer};
+use std::sync::Arc;
+use std::time::Duration;
+use twitch_api_rs::auth::scopes::Scope;
+use twitch_api_rs::auth::validate::*;
+use twitch_api_rs::auth::AuthToken;
+use twitch_api_rs::client::*;
+
+const APP_TOKEN: &str = r#"{"access_token":"app_token","expires_in":5000,"token_type":"bearer"}"#;
+const NEW_APP_TOKEN: &str =
+    r#"{"access_token":"new_app_token","expires_in":5000,"token_type":"bearer"}"#;
+const APP_VALID: &str = r#"{"client_id":"client_id","scopes":null,"expires_in":5000}"#;
+const INVALID: &str = r#"{"status":401,"message":"invalid access token"}"#;
+
+async fn app_client(server: &MockServer) -> Client<ClientCredentials> {
+    let client = Client::builder()
+        .auth_base(format!("{}/oauth2", server.base))
+        .build()
+        .unwrap();
+
+    match client
+        .client_auth("client_id".into(), "client_secret".into())
+        .await
+    {
+        Ok(client) => client,
+        Err((e, _)) => panic!("Could not authorize: {}", e),
+    }
+}
+
+#[test]
+fn tokens_have_validated_scopes() {
+    let validated: ValidateResponse = serde_json::from_str(
+        r#"{
+            "client_id": "client_id",
+            "login": "thehoodlum12",
+            "scopes": ["user:read:email", "clips:edit"],
+            "user_id": "477906794",
+            "expires_in": 5000
+        }"#,
+    )
+    .unwrap();
+
+    assert_eq!(validated.expires_after(), Some(Duration::from_secs(5000)));
+
+    let token = validated.user_token("access", Some("refresh".to_string()));
+    assert!(token.scopes().contains(Scope::UserReadEmail));
+    assert!(token.scopes().contains(Scope::ClipsEdit));
+    assert_eq!(
+        token.login.as_deref().map(|l| l.as_str()),
+        Some("thehoodlum12")
+    );
+
+    let token = validated.app_token("access");
+    assert!(token.scopes().contains(Scope::ClipsEdit));
+
+    let app: ValidateResponse = serde_json::from_str(APP_VALID).unwrap();
+    assert!(app.user_id.is_none());
+    assert_eq!(app.app_token("access").scopes().spec_iter().count(), 0);
+}
+
+#[tokio::test]
+async fn invalid_token_is_refreshed() {
+    let server = MockServer::start(vec![
+        Canned::json(200, APP_TOKEN),
+        Canned::json(401, INVALID),
+        Canned::json(200, NEW_APP_TOKEN),
+        Canned::json(200, APP_VALID),
+    ])
+    .await;
+    let client = app_client(&server).await;
+
+    let validated = client.validate().await.expect("Could not validate token");
+    assert_eq!(&*validated.client_id, "client_id");
+    assert_eq!(client.token().token, "new_app_token");
+
+    let received = server.received();
+    assert!(received[1].request_line.starts_with("GET /oauth2/validate"));
+    assert_eq!(received[1].header("authorization"), Some("OAuth app_token"));
+    assert_eq!(
+        received[3].header("authorization"),
+        Some("OAuth new_app_token")
+    );
+}
+
+#[tokio::test]
+async fn validation_runs_until_client_is_dropped() {
+    let server = MockServer::start(vec![
+        Canned::json(200, APP_TOKEN),
+        Canned::json(200, APP_VALID),
+        Canned::json(200, APP_VALID),
+    ])
+    .await;
+    let client = Arc::new(app_client(&server).await);
+
+    let task = client.spawn_validation(Duration::from_millis(100));
+    tokio::time::sleep(Duration::from_millis(150)).await;
+    drop(client);
+
+    tokio::time::timeout(Duration::from_secs(5), task)
+        .await
+        .expect("Validation did not stop")
+        .unwrap();
+
+    let validations = server
+        .received()
+        .iter()
+        .filter(|r| r.request_line.contains("/oauth2/validate"))
+        .count();
+    assert_eq!(validations, 2);
+}